@@ -2,10 +2,18 @@
 
 use std::{fmt, sync};
 
-use crate::token::TokenCache;
+use crate::{
+    metrics::{Metrics, Operation},
+    middleware::{Middleware, Next},
+    retry,
+    signer::Signer,
+    token::TokenCache,
+    RetryPolicy,
+};
 
 mod bucket;
 mod bucket_access_control;
+mod builder;
 mod default_object_access_control;
 mod hmac_key;
 mod object;
@@ -13,6 +21,7 @@ mod object_access_control;
 
 pub use bucket::BucketClient;
 pub use bucket_access_control::BucketAccessControlClient;
+pub use builder::ClientBuilder;
 pub use default_object_access_control::DefaultObjectAccessControlClient;
 pub use hmac_key::HmacKeyClient;
 pub use object::ObjectClient;
@@ -20,9 +29,32 @@ pub use object_access_control::ObjectAccessControlClient;
 
 /// The primary entrypoint to perform operations with Google Cloud Storage.
 pub struct Client {
-    client: reqwest::Client,
-    /// Static `Token` struct that caches
-    token_cache: sync::Arc<dyn crate::TokenCache + Send>,
+    pub(crate) client: reqwest::Client,
+    /// Static `Token` struct that caches, or `None` for anonymous clients
+    token_cache: Option<sync::Arc<dyn crate::TokenCache + Send>>,
+    /// Held while refreshing a token cache that does not have a `refresh_lock` of its own
+    refresh_lock: sync::Arc<tokio::sync::Mutex<()>>,
+    signer: sync::Arc<dyn Signer>,
+    /// The endpoint of the JSON API, such as `https://storage.googleapis.com/storage/v1`
+    pub(crate) api_endpoint: String,
+    /// The endpoint for media uploads, such as `https://storage.googleapis.com/upload/storage/v1`
+    pub(crate) upload_endpoint: String,
+    /// Headers that are sent with every request
+    default_headers: reqwest::header::HeaderMap,
+    /// The project in which buckets and HMAC keys live, or `None` for the project of the service
+    /// account
+    project_id: Option<String>,
+    /// The service account for which HMAC keys are created, or `None` for the service account
+    /// found in the `SERVICE_ACCOUNT` environment parameter
+    service_account_email: Option<String>,
+    /// The project that requests are billed to, such as for buckets with requester pays enabled
+    user_project: UserProject,
+    /// Determines how failed requests are retried
+    retry_policy: RetryPolicy,
+    /// The middleware that every request passes through, in order
+    middleware: Vec<sync::Arc<dyn Middleware>>,
+    /// Receives the metrics of every request, if set
+    metrics: Option<sync::Arc<dyn Metrics>>,
 }
 
 impl fmt::Debug for Client {
@@ -30,16 +62,23 @@ impl fmt::Debug for Client {
         f.debug_struct("Client")
             .field("client", &self.client)
             .field("token_cache", &"<opaque>")
+            .field("signer", &"<opaque>")
+            .field("api_endpoint", &self.api_endpoint)
+            .field("upload_endpoint", &self.upload_endpoint)
+            .field("default_headers", &self.default_headers)
+            .field("project_id", &self.project_id)
+            .field("service_account_email", &self.service_account_email)
+            .field("user_project", &self.user_project.user_project)
+            .field("retry_policy", &self.retry_policy)
+            .field("middleware", &self.middleware.len())
+            .field("metrics", &self.metrics.is_some())
             .finish()
     }
 }
 
 impl Default for Client {
     fn default() -> Self {
-        Self {
-            client: Default::default(),
-            token_cache: sync::Arc::new(crate::Token::default()),
-        }
+        ClientBuilder::new().build_infallible()
     }
 }
 
@@ -48,7 +87,7 @@ impl Client {
     /// credentials from the following locations:
     ///
     /// 1. Checks for the environment variable `SERVICE_ACCOUNT`, and if it exists, reads the file
-    /// at the path specified there as a credentials json file.
+    ///    at the path specified there as a credentials json file.
     /// 2. It attemps to do the same with the `GOOGLE_APPLICATION_CREDENTIALS` var.
     /// 3. It reads the `SERVICE_ACCOUNT_JSON` environment variable directly as json and uses that
     /// 4. It attemps to do the same with the `GOOGLE_APPLICATION_CREDENTIALS_JSON` var.
@@ -58,49 +97,618 @@ impl Client {
 
     /// Initializer with a provided refreshable token
     pub fn with_cache(token: impl TokenCache + Send + 'static) -> Self {
-        Self {
-            client: Default::default(),
-            token_cache: sync::Arc::new(token),
+        ClientBuilder::new().token_cache(token).build_infallible()
+    }
+
+    /// Returns a [`ClientBuilder`] to configure the endpoints, HTTP client and headers of a
+    /// client.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Constructs a client that does not authenticate its requests, which allows reading from
+    /// public buckets without any credentials. Requests that require permissions that are not
+    /// granted to `allUsers` fail.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::Client;
+    ///
+    /// let client = Client::anonymous();
+    /// let object = client.object().read("gcp-public-data-landsat", "index.csv.gz").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn anonymous() -> Self {
+        ClientBuilder::new().anonymous().build_infallible()
+    }
+
+    /// Sets the signer that produces the signatures of the signed urls created by this client,
+    /// for example to sign through the IAM API when no private key is available. By default,
+    /// urls are signed with the service account found in the `SERVICE_ACCOUNT` environment
+    /// parameter.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use cloud_storage::{Client, IamSigner};
+    ///
+    /// let client = Client::default()
+    ///     .with_signer(IamSigner::new("signer@my-project.iam.gserviceaccount.com"));
+    /// ```
+    pub fn with_signer(mut self, signer: impl Signer + 'static) -> Self {
+        self.signer = sync::Arc::new(signer);
+        self
+    }
+
+    /// Returns the signer that produces the signatures of the signed urls created by this client.
+    pub fn signer(&self) -> &dyn Signer {
+        self.signer.as_ref()
+    }
+
+    /// Spawns a task on the current tokio runtime that refreshes the token of this client shortly
+    /// before it expires, so that requests do not have to wait for a new token to be fetched. The
+    /// task stops by itself when the client is dropped. For anonymous clients the task does
+    /// nothing.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use cloud_storage::Client;
+    ///
+    /// let client = Client::default();
+    /// client.spawn_token_refresh();
+    /// # }
+    /// ```
+    pub fn spawn_token_refresh(&self) -> tokio::task::JoinHandle<()> {
+        match &self.token_cache {
+            Some(token_cache) => crate::token::spawn_refresh(
+                sync::Arc::downgrade(token_cache),
+                self.refresh_lock.clone(),
+                self.client.clone(),
+            ),
+            None => tokio::spawn(async {}),
         }
     }
 
     /// Operations on [`Bucket`](crate::bucket::Bucket)s.
     pub fn bucket(&self) -> BucketClient<'_> {
-        BucketClient(self)
+        BucketClient(self, self.user_project.clone())
     }
 
     /// Operations on [`BucketAccessControl`](crate::bucket_access_control::BucketAccessControl)s.
     pub fn bucket_access_control(&self) -> BucketAccessControlClient<'_> {
-        BucketAccessControlClient(self)
+        BucketAccessControlClient(self, self.user_project.clone())
     }
 
     /// Operations on [`DefaultObjectAccessControl`](crate::default_object_access_control::DefaultObjectAccessControl)s.
     pub fn default_object_access_control(&self) -> DefaultObjectAccessControlClient<'_> {
-        DefaultObjectAccessControlClient(self)
+        DefaultObjectAccessControlClient(self, self.user_project.clone())
     }
 
     /// Operations on [`HmacKey`](crate::hmac_key::HmacKey)s.
     pub fn hmac_key(&self) -> HmacKeyClient<'_> {
-        HmacKeyClient(self)
+        HmacKeyClient(self, self.user_project.clone())
     }
 
     /// Operations on [`Object`](crate::object::Object)s.
     pub fn object(&self) -> ObjectClient<'_> {
-        ObjectClient(self)
+        ObjectClient(self, Default::default(), self.user_project.clone())
     }
 
     /// Operations on [`ObjectAccessControl`](crate::object_access_control::ObjectAccessControl)s.
     pub fn object_access_control(&self) -> ObjectAccessControlClient<'_> {
-        ObjectAccessControlClient(self)
+        ObjectAccessControlClient(self, self.user_project.clone())
     }
 
-    async fn get_headers(&self) -> crate::Result<reqwest::header::HeaderMap> {
-        let mut result = reqwest::header::HeaderMap::new();
-        let token = self.token_cache.get(&self.client).await?;
-        result.insert(
-            reqwest::header::AUTHORIZATION,
-            format!("Bearer {}", token).parse().unwrap(),
-        );
+    /// Operations on objects through the XML API, with requests that are signed with the HMAC
+    /// key of `signer` instead of authenticated with the token of this client.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::Client;
+    ///
+    /// let client = Client::default();
+    /// let hmac_key = client.hmac_key().create().await?;
+    /// let bytes = client.xml(&hmac_key).get("my_bucket", "path/to/my/file.png").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "xml")]
+    pub fn xml(&self, signer: impl Into<crate::HmacSigner>) -> crate::xml::XmlClient<'_> {
+        crate::xml::XmlClient::new(self, Some(signer.into()))
+    }
+
+    /// Operations on objects through the XML API, with requests that are authenticated with the
+    /// token of this client, like those of the JSON API.
+    #[cfg(feature = "xml")]
+    pub fn xml_oauth(&self) -> crate::xml::XmlClient<'_> {
+        crate::xml::XmlClient::new(self, None)
+    }
+
+    /// Sends `request` through the middleware of this client, retrying it according to its
+    /// `RetryPolicy`. Responses with a non 2xx status code are turned into an `Error::Http`.
+    pub(crate) async fn execute(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> crate::Result<reqwest::Response> {
+        self.execute_as(request, false).await
+    }
+
+    /// The same as `execute`, but retries `request` whatever its method, because sending it more
+    /// than once has the same effect as sending it once.
+    pub(crate) async fn execute_idempotent(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> crate::Result<reqwest::Response> {
+        self.execute_as(request, true).await
+    }
+
+    async fn execute_as(
+        &self,
+        request: reqwest::RequestBuilder,
+        idempotent: bool,
+    ) -> crate::Result<reqwest::Response> {
+        let response = self.execute_with_retries(request, idempotent).await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(crate::error::HttpError::from_response(response)
+                .await
+                .into())
+        }
+    }
+
+    async fn execute_with_retries(
+        &self,
+        request: reqwest::RequestBuilder,
+        idempotent: bool,
+    ) -> crate::Result<reqwest::Response> {
+        let request = request
+            .header(retry::IDEMPOTENCY_TOKEN, retry::idempotency_token())
+            .build()?;
+        let operation = Operation::of(&request, &[&self.upload_endpoint, &self.api_endpoint]);
+        let bytes_sent = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| bytes.len() as u64)
+            .or_else(|| {
+                let length = request.headers().get(reqwest::header::CONTENT_LENGTH)?;
+                length.to_str().ok()?.parse().ok()
+            });
+        #[cfg(feature = "tracing")]
+        let span = {
+            let span = tracing::info_span!(
+                "cloud_storage",
+                operation = operation.name,
+                method = %request.method(),
+                bucket = tracing::field::Empty,
+                object = tracing::field::Empty,
+                generation = tracing::field::Empty,
+                bytes_sent = tracing::field::Empty,
+                bytes_received = tracing::field::Empty,
+                retries = tracing::field::Empty,
+                status = tracing::field::Empty,
+            );
+            let generation = request.url().query_pairs().find(|(k, _)| k == "generation");
+            let fields = [
+                ("bucket", operation.bucket.as_deref()),
+                ("object", operation.object.as_deref()),
+                ("generation", generation.as_ref().map(|(_, v)| v.as_ref())),
+            ];
+            for (field, value) in fields.iter() {
+                if let Some(value) = value {
+                    span.record(*field, value);
+                }
+            }
+            if let Some(bytes_sent) = bytes_sent {
+                span.record("bytes_sent", bytes_sent);
+            }
+            span
+        };
+
+        let start = std::time::Instant::now();
+        let attempts = self.send_attempts(request, idempotent, start);
+        #[cfg(feature = "tracing")]
+        let attempts = tracing::Instrument::instrument(attempts, span.clone());
+        let (result, attempts) = attempts.await;
+        let elapsed = start.elapsed();
+        let bytes_received = result.as_ref().ok().and_then(|r| r.content_length());
+
+        #[cfg(feature = "tracing")]
+        span.in_scope(|| {
+            span.record("retries", attempts - 1);
+            if let Some(bytes_received) = bytes_received {
+                span.record("bytes_received", bytes_received);
+            }
+            match &result {
+                Ok(response) => {
+                    let generation = response.headers().get("x-goog-generation");
+                    if let Some(generation) = generation.and_then(|g| g.to_str().ok()) {
+                        span.record("generation", generation);
+                    }
+                    span.record("status", response.status().as_u16());
+                    tracing::debug!(?elapsed, "request finished");
+                }
+                Err(error) => tracing::warn!(?elapsed, %error, "request failed"),
+            }
+        });
+
+        if let Some(metrics) = &self.metrics {
+            let status = match &result {
+                Ok(response) => response.status().as_str().to_string(),
+                Err(_) => "error".to_string(),
+            };
+            let labels = [("operation", operation.name), ("status", status.as_str())];
+            metrics.increment_counter(crate::metrics::REQUESTS, 1, &labels);
+            let seconds = elapsed.as_secs_f64();
+            metrics.record_histogram(crate::metrics::REQUEST_DURATION, seconds, &labels);
+            let labels = &labels[..1];
+            if attempts > 1 {
+                metrics.increment_counter(crate::metrics::RETRIES, attempts - 1, labels);
+            }
+            if let Some(bytes_sent) = bytes_sent {
+                metrics.increment_counter(crate::metrics::BYTES_SENT, bytes_sent, labels);
+                if let Some(throughput) = crate::metrics::throughput(bytes_sent, elapsed) {
+                    let labels = [labels[0], ("direction", "upload")];
+                    metrics.record_histogram(crate::metrics::THROUGHPUT, throughput, &labels);
+                }
+            }
+            if let Some(bytes_received) = bytes_received {
+                metrics.increment_counter(crate::metrics::BYTES_RECEIVED, bytes_received, labels);
+            }
+        }
+        result
+    }
+
+    /// Sends `request` until it succeeds or may no longer be retried, and returns the last result
+    /// together with the number of attempts that were made.
+    async fn send_attempts(
+        &self,
+        mut request: reqwest::Request,
+        idempotent: bool,
+        start: std::time::Instant,
+    ) -> (crate::Result<reqwest::Response>, u64) {
+        let may_retry = if idempotent {
+            self.retry_policy.retries()
+        } else {
+            self.retry_policy.may_retry(&request)
+        };
+        let mut attempt = 1;
+        loop {
+            // requests with a streaming body cannot be cloned, and are therefore sent only once
+            let next = if may_retry { request.try_clone() } else { None };
+            let result = Next::new(&self.client, &self.middleware).run(request).await;
+            let next = match next {
+                Some(next) => next,
+                None => return (result, attempt),
+            };
+            let retryable = match &result {
+                Ok(response) => retry::is_retryable_status(response.status()),
+                Err(e) => e.is_retryable(),
+            };
+            if !retryable {
+                return (result, attempt);
+            }
+            let backoff = match self.retry_policy.backoff(attempt as u32, start.elapsed()) {
+                Some(backoff) => backoff,
+                None => return (result, attempt),
+            };
+            #[cfg(feature = "tracing")]
+            match &result {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    tracing::info!(attempt, status, ?backoff, "retrying request");
+                }
+                Err(error) => tracing::info!(attempt, %error, ?backoff, "retrying request"),
+            }
+            tokio::time::sleep(backoff).await;
+            request = next;
+            attempt += 1;
+        }
+    }
+
+    /// Records the throughput of a download of `bytes` into memory, which took `elapsed` from
+    /// sending the request until the last byte was received.
+    pub(crate) fn record_download(
+        &self,
+        operation: &'static str,
+        bytes: u64,
+        elapsed: std::time::Duration,
+    ) {
+        let metrics = match &self.metrics {
+            Some(metrics) => metrics,
+            None => return,
+        };
+        if let Some(throughput) = crate::metrics::throughput(bytes, elapsed) {
+            let labels = [("operation", operation), ("direction", "download")];
+            metrics.record_histogram(crate::metrics::THROUGHPUT, throughput, &labels);
+        }
+    }
+
+    pub(crate) fn project_id(&self) -> &str {
+        self.project_id
+            .as_deref()
+            .unwrap_or_else(|| &crate::SERVICE_ACCOUNT.project_id)
+    }
+
+    pub(crate) fn service_account_email(&self) -> &str {
+        self.service_account_email
+            .as_deref()
+            .unwrap_or_else(|| &crate::SERVICE_ACCOUNT.client_email)
+    }
+
+    /// The endpoint of the XML API and of signed urls, on the host of the JSON API endpoint, such
+    /// as `https://storage.googleapis.com` for `https://storage.googleapis.com/storage/v1`.
+    pub(crate) fn xml_endpoint(&self) -> String {
+        match self.api_endpoint.strip_suffix("/storage/v1") {
+            Some(endpoint) => endpoint.to_string(),
+            None => match reqwest::Url::parse(&self.api_endpoint) {
+                Ok(url) => url.origin().ascii_serialization(),
+                Err(_) => self.api_endpoint.clone(),
+            },
+        }
+    }
+
+    /// The headers that are sent with every request, apart from the authorization.
+    #[cfg(feature = "xml")]
+    pub(crate) fn default_headers(&self) -> &reqwest::header::HeaderMap {
+        &self.default_headers
+    }
+
+    pub(crate) async fn get_headers(&self) -> crate::Result<reqwest::header::HeaderMap> {
+        let mut result = self.default_headers.clone();
+        if let Some(token_cache) = &self.token_cache {
+            let token =
+                crate::token::get(token_cache.as_ref(), &self.refresh_lock, &self.client).await?;
+            result.insert(
+                reqwest::header::AUTHORIZATION,
+                format!("Bearer {}", token).parse().unwrap(),
+            );
+        }
         Ok(result)
     }
 }
+
+/// The `userProject` query parameter, which names the project that is billed for a request.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UserProject {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_project: Option<String>,
+}
+
+impl UserProject {
+    pub(crate) fn new(project: String) -> Self {
+        Self {
+            user_project: Some(project),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<&str> {
+        self.user_project.as_deref()
+    }
+}
+
+/// Sends requests through the retry policy of a [`Client`].
+#[async_trait::async_trait]
+pub(crate) trait SendExt {
+    /// Sends the request using `client`.
+    async fn send_with(self, client: &Client) -> crate::Result<reqwest::Response>;
+
+    /// Sends the request using `client`, retrying it even when its method is not idempotent.
+    /// Only use this for requests that can safely be sent more than once.
+    async fn send_idempotent_with(self, client: &Client) -> crate::Result<reqwest::Response>;
+}
+
+#[async_trait::async_trait]
+impl SendExt for reqwest::RequestBuilder {
+    async fn send_with(self, client: &Client) -> crate::Result<reqwest::Response> {
+        client.execute(self).await
+    }
+
+    async fn send_idempotent_with(self, client: &Client) -> crate::Result<reqwest::Response> {
+        client.execute_idempotent(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn anonymous_headers() -> Result<(), Box<dyn std::error::Error>> {
+        let headers = Client::anonymous().get_headers().await?;
+        assert!(!headers.contains_key(reqwest::header::AUTHORIZATION));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retries() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::middleware::mock::{response, Canned};
+
+        let policy = RetryPolicy::new().initial_backoff(std::time::Duration::from_millis(1));
+        let canned = Canned::responses(vec![
+            response(503, &[], ""),
+            response(429, &[], ""),
+            response(200, &[], ""),
+        ]);
+        let client = Client::builder()
+            .anonymous()
+            .endpoint("http://storage.invalid")
+            .retry_policy(policy.clone())
+            .middleware(canned.clone())
+            .build()?;
+        let url = format!("{}/b/bucket", client.api_endpoint);
+        let status = client.execute(client.client.get(&url)).await?.status();
+        assert_eq!(status, 200);
+        assert_eq!(canned.requests().len(), 3);
+
+        // mutations without preconditions are not retried
+        let canned = Canned::responses(vec![response(503, &[], ""), response(200, &[], "")]);
+        let client = Client::builder()
+            .anonymous()
+            .endpoint("http://storage.invalid")
+            .retry_policy(policy)
+            .middleware(canned.clone())
+            .build()?;
+        let url = format!("{}/b/bucket", client.api_endpoint);
+        let error = client
+            .execute(client.client.delete(&url))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.status(),
+            Some(reqwest::StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert!(error.is_retryable());
+        assert_eq!(canned.requests().len(), 1);
+        Ok(())
+    }
+
+    // Records the query and `x-goog-user-project` header of every request, and answers it itself.
+    #[derive(Default)]
+    struct Recorder(std::sync::Mutex<Vec<(Option<String>, Option<String>)>>);
+
+    #[async_trait::async_trait]
+    impl Middleware for sync::Arc<Recorder> {
+        async fn handle(
+            &self,
+            request: reqwest::Request,
+            _next: Next<'_>,
+        ) -> crate::Result<reqwest::Response> {
+            let query = request.url().query().map(String::from);
+            let header = request.headers().get("x-goog-user-project");
+            let header = header.and_then(|h| h.to_str().ok()).map(String::from);
+            self.0.lock().unwrap().push((query, header));
+            Ok(http::Response::new("hello").into())
+        }
+    }
+
+    #[tokio::test]
+    async fn user_project() -> Result<(), Box<dyn std::error::Error>> {
+        let recorder = sync::Arc::new(Recorder::default());
+        let client = Client::builder()
+            .anonymous()
+            .endpoint("http://storage.invalid")
+            .user_project("billed")
+            .quota_project("quota")
+            .middleware(recorder.clone())
+            .build()?;
+        client.object().download("bucket", "object").await?;
+        client
+            .object()
+            .with_user_project("other")
+            .download("bucket", "object")
+            .await?;
+        let recorded = recorder.0.lock().unwrap().clone();
+        assert_eq!(
+            recorded,
+            [
+                (
+                    Some("alt=media&userProject=billed".to_string()),
+                    Some("quota".to_string())
+                ),
+                (
+                    Some("alt=media&userProject=other".to_string()),
+                    Some("quota".to_string())
+                ),
+            ]
+        );
+
+        let client = Client::builder()
+            .anonymous()
+            .endpoint("http://storage.invalid")
+            .middleware(recorder.clone())
+            .build()?;
+        client.object().download("bucket", "object").await?;
+        let recorded = recorder.0.lock().unwrap().pop().unwrap();
+        assert_eq!(recorded, (Some("alt=media".to_string()), None));
+        Ok(())
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn sync_user_project() -> Result<(), Box<dyn std::error::Error>> {
+        let recorder = sync::Arc::new(Recorder::default());
+        let client = Client::builder()
+            .anonymous()
+            .endpoint("http://storage.invalid")
+            .user_project("billed")
+            .middleware(recorder.clone())
+            .build_sync()?;
+        client.object().download("bucket", "object")?;
+        client
+            .object()
+            .with_user_project("other")
+            .download("bucket", "object")?;
+        // the answer of the recorder is not valid JSON, but the requests have been made
+        let bucket = client.bucket().with_user_project("other").read("bucket");
+        assert!(bucket.is_err());
+        let acls = client
+            .object_access_control()
+            .with_user_project("other")
+            .list("bucket", "object");
+        assert!(acls.is_err());
+        let queries: Vec<_> = recorder
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(query, _)| query.clone().unwrap_or_default())
+            .collect();
+        assert_eq!(
+            queries,
+            [
+                "alt=media&userProject=billed",
+                "alt=media&userProject=other",
+                "userProject=other",
+                "userProject=other",
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn signed_urls() -> Result<(), Box<dyn std::error::Error>> {
+        let service_account = crate::resources::service_account::ServiceAccount::test();
+        let client = Client::builder()
+            .anonymous()
+            .endpoint("https://storage.googleapis.com")
+            .signer(crate::ServiceAccountSigner::new(service_account.clone()))
+            .user_project("billed")
+            .build()?;
+        let url = client
+            .object()
+            .signed_upload_url("my-bucket", "new file.txt", 60)
+            .await?;
+        assert!(url.starts_with("https://storage.googleapis.com/my-bucket/new%20file.txt?"));
+        let credential = crate::object::percent_encode(&service_account.client_email);
+        assert!(url.contains(&format!("X-Goog-Credential={}%2F", credential)));
+        assert!(url.contains("X-Goog-Expires=60&"));
+        assert!(url.contains("&userProject=billed&X-Goog-Signature="));
+
+        let url = client
+            .object()
+            .with_user_project("other")
+            .signed_download_url("my-bucket", "file.txt", 60)
+            .await?;
+        assert!(url.contains("&userProject=other&X-Goog-Signature="));
+
+        // urls point at the emulator the client talks to
+        let client = Client::builder()
+            .emulator_host("localhost:4443")
+            .signer(crate::ServiceAccountSigner::new(service_account))
+            .build()?;
+        let url = client
+            .object()
+            .signed_download_url("my-bucket", "file.txt", 60)
+            .await?;
+        assert!(url.starts_with("http://localhost:4443/my-bucket/file.txt?"));
+        Ok(())
+    }
+}
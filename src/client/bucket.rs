@@ -1,5 +1,6 @@
 use crate::{
     bucket::{IamPolicy, TestIamPermission},
+    client::SendExt,
     error::GoogleResponse,
     object::percent_encode,
     resources::common::ListResponse,
@@ -8,9 +9,31 @@ use crate::{
 
 /// Operations on [`Bucket`]()s.
 #[derive(Debug)]
-pub struct BucketClient<'a>(pub(super) &'a super::Client);
+pub struct BucketClient<'a>(pub(super) &'a super::Client, pub(super) super::UserProject);
 
 impl<'a> BucketClient<'a> {
+    /// Bills the requests made by this client, including the IAM operations, to `project`. This
+    /// overrides the project set with `ClientBuilder::user_project`.
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::Client;
+    ///
+    /// let client = Client::default();
+    /// let bucket = client
+    ///     .bucket()
+    ///     .with_user_project("my-project")
+    ///     .read("requester-pays-bucket")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = super::UserProject::new(project.into());
+        self
+    }
+
     /// Creates a new `Bucket`. There are many options that you can provide for creating a new
     /// bucket, so the `NewBucket` resource contains all of them. Note that `NewBucket` implements
     /// `Default`, so you don't have to specify the fields you're not using. And error is returned
@@ -35,8 +58,8 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub async fn create(&self, new_bucket: &NewBucket) -> crate::Result<Bucket> {
-        let url = format!("{}/b/", crate::BASE_URL);
-        let project = &crate::SERVICE_ACCOUNT.project_id;
+        let url = format!("{}/b/", self.0.api_endpoint);
+        let project = self.0.project_id();
         let query = [("project", project)];
         let result: GoogleResponse<Bucket> = self
             .0
@@ -45,7 +68,8 @@ impl<'a> BucketClient<'a> {
             .headers(self.0.get_headers().await?)
             .query(&query)
             .json(new_bucket)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -73,27 +97,17 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub async fn list(&self) -> crate::Result<Vec<Bucket>> {
-        let url = dbg!(format!("{}/b/", crate::BASE_URL));
-        let project = &crate::SERVICE_ACCOUNT.project_id;
+        let url = format!("{}/b/", self.0.api_endpoint);
+        let project = self.0.project_id();
         let query = [("project", project)];
-        dbg!(
-            self.0
-                .client
-                .get(&url)
-                .headers(self.0.get_headers().await?)
-                .query(&query)
-                .send()
-                .await?
-                .text()
-                .await
-        )?;
         let result: GoogleResponse<ListResponse<Bucket>> = self
             .0
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
             .query(&query)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -125,13 +139,14 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub async fn read(&self, name: &str) -> crate::Result<Bucket> {
-        let url = format!("{}/b/{}", crate::BASE_URL, percent_encode(name),);
+        let url = format!("{}/b/{}", self.0.api_endpoint, percent_encode(name),);
         let result: GoogleResponse<Bucket> = self
             .0
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -170,14 +185,15 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub async fn update(&self, bucket: &Bucket) -> crate::Result<Bucket> {
-        let url = format!("{}/b/{}", crate::BASE_URL, percent_encode(&bucket.name),);
+        let url = format!("{}/b/{}", self.0.api_endpoint, percent_encode(&bucket.name),);
         let result: GoogleResponse<Bucket> = self
             .0
             .client
             .put(&url)
             .headers(self.0.get_headers().await?)
             .json(bucket)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -211,19 +227,15 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub async fn delete(&self, bucket: Bucket) -> crate::Result<()> {
-        let url = format!("{}/b/{}", crate::BASE_URL, percent_encode(&bucket.name));
-        let response = self
-            .0
+        let url = format!("{}/b/{}", self.0.api_endpoint, percent_encode(&bucket.name));
+        self.0
             .client
             .delete(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?;
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::Error::Google(response.json().await?))
-        }
+        Ok(())
     }
 
     /// Returns the [IAM Policy](https://cloud.google.com/iam/docs/) for this bucket.
@@ -249,13 +261,18 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub async fn get_iam_policy(&self, bucket: &Bucket) -> crate::Result<IamPolicy> {
-        let url = format!("{}/b/{}/iam", crate::BASE_URL, percent_encode(&bucket.name));
+        let url = format!(
+            "{}/b/{}/iam",
+            self.0.api_endpoint,
+            percent_encode(&bucket.name)
+        );
         let result: GoogleResponse<IamPolicy> = self
             .0
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -304,14 +321,19 @@ impl<'a> BucketClient<'a> {
         bucket: &Bucket,
         iam: &IamPolicy,
     ) -> crate::Result<IamPolicy> {
-        let url = format!("{}/b/{}/iam", crate::BASE_URL, percent_encode(&bucket.name));
+        let url = format!(
+            "{}/b/{}/iam",
+            self.0.api_endpoint,
+            percent_encode(&bucket.name)
+        );
         let result: GoogleResponse<IamPolicy> = self
             .0
             .client
             .put(&url)
             .headers(self.0.get_headers().await?)
             .json(iam)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -347,7 +369,7 @@ impl<'a> BucketClient<'a> {
         }
         let url = format!(
             "{}/b/{}/iam/testPermissions",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&bucket.name)
         );
         let result: GoogleResponse<TestIamPermission> = self
@@ -356,7 +378,8 @@ impl<'a> BucketClient<'a> {
             .get(&url)
             .headers(self.0.get_headers().await?)
             .query(&[("permissions", permission)])
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
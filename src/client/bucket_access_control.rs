@@ -1,14 +1,25 @@
 use crate::{
     bucket_access_control::{BucketAccessControl, Entity, NewBucketAccessControl},
+    client::SendExt,
     error::GoogleResponse,
     object::percent_encode,
     resources::common::ListResponse,
 };
 
 /// Operations on [`BucketAccessControl`](BucketAccessControl)s.
-pub struct BucketAccessControlClient<'a>(pub(super) &'a super::Client);
+pub struct BucketAccessControlClient<'a>(
+    pub(super) &'a super::Client,
+    pub(super) super::UserProject,
+);
 
 impl<'a> BucketAccessControlClient<'a> {
+    /// Bills the requests made by this client to `project`, which is needed to manage the access
+    /// controls of a requester pays bucket. See `BucketClient::with_user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = super::UserProject::new(project.into());
+        self
+    }
+
     /// Create a new `BucketAccessControl` using the provided `NewBucketAccessControl`, related to
     /// the `Bucket` provided by the `bucket_name` argument.
     ///
@@ -38,14 +49,15 @@ impl<'a> BucketAccessControlClient<'a> {
         bucket: &str,
         new_bucket_access_control: &NewBucketAccessControl,
     ) -> crate::Result<BucketAccessControl> {
-        let url = format!("{}/b/{}/acl", crate::BASE_URL, percent_encode(bucket));
+        let url = format!("{}/b/{}/acl", self.0.api_endpoint, percent_encode(bucket));
         let result: GoogleResponse<BucketAccessControl> = self
             .0
             .client
             .post(&url)
             .headers(self.0.get_headers().await?)
             .json(new_bucket_access_control)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -74,13 +86,14 @@ impl<'a> BucketAccessControlClient<'a> {
     /// # }
     /// ```
     pub async fn list(&self, bucket: &str) -> crate::Result<Vec<BucketAccessControl>> {
-        let url = format!("{}/b/{}/acl", crate::BASE_URL, percent_encode(bucket));
+        let url = format!("{}/b/{}/acl", self.0.api_endpoint, percent_encode(bucket));
         let result: GoogleResponse<ListResponse<BucketAccessControl>> = self
             .0
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -111,7 +124,7 @@ impl<'a> BucketAccessControlClient<'a> {
     pub async fn read(&self, bucket: &str, entity: &Entity) -> crate::Result<BucketAccessControl> {
         let url = format!(
             "{}/b/{}/acl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(&entity.to_string())
         );
@@ -120,7 +133,8 @@ impl<'a> BucketAccessControlClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -156,7 +170,7 @@ impl<'a> BucketAccessControlClient<'a> {
     ) -> crate::Result<BucketAccessControl> {
         let url = format!(
             "{}/b/{}/acl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&bucket_access_control.bucket),
             percent_encode(&bucket_access_control.entity.to_string()),
         );
@@ -166,7 +180,8 @@ impl<'a> BucketAccessControlClient<'a> {
             .put(&url)
             .headers(self.0.get_headers().await?)
             .json(bucket_access_control)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -198,21 +213,17 @@ impl<'a> BucketAccessControlClient<'a> {
     pub async fn delete(&self, bucket_access_control: BucketAccessControl) -> crate::Result<()> {
         let url = format!(
             "{}/b/{}/acl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&bucket_access_control.bucket),
             percent_encode(&bucket_access_control.entity.to_string()),
         );
-        let response = self
-            .0
+        self.0
             .client
             .delete(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?;
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::Error::Google(response.json().await?))
-        }
+        Ok(())
     }
 }
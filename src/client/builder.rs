@@ -0,0 +1,389 @@
+use std::{sync, time::Duration};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+
+use crate::{metrics::Metrics, middleware::Middleware, signer::Signer, token::TokenCache};
+
+// Object uploads has its own url for some reason
+const UPLOAD_URL: &str = "https://storage.googleapis.com/upload/storage/v1";
+
+// The `x-goog-api-client` header that is sent unless another one is configured
+const API_CLIENT: &str = concat!("cloud-storage-rs/", env!("CARGO_PKG_VERSION"));
+
+/// A builder for a [`Client`](crate::Client), which configures the endpoints it talks to, the
+/// HTTP client it uses and the headers it sends.
+///
+/// ### Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+/// use cloud_storage::Client;
+///
+/// let client = Client::builder()
+///     .endpoint("https://storage.europe-west3.rep.googleapis.com")
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(60))
+///     .user_agent("my-app/1.0")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ClientBuilder {
+    client: Option<reqwest::Client>,
+    token_cache: Option<sync::Arc<dyn TokenCache + Send>>,
+    signer: sync::Arc<dyn Signer>,
+    api_endpoint: String,
+    upload_endpoint: String,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    default_headers: HeaderMap,
+    user_agent: Option<String>,
+    api_client: Option<String>,
+    project_id: Option<String>,
+    service_account_email: Option<String>,
+    user_project: Option<String>,
+    quota_project: Option<String>,
+    retry_policy: crate::RetryPolicy,
+    middleware: Vec<sync::Arc<dyn Middleware>>,
+    metrics: Option<sync::Arc<dyn Metrics>>,
+}
+
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("client", &self.client)
+            .field(
+                "token_cache",
+                &self.token_cache.as_ref().map(|_| "<opaque>"),
+            )
+            .field("signer", &"<opaque>")
+            .field("api_endpoint", &self.api_endpoint)
+            .field("upload_endpoint", &self.upload_endpoint)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("timeout", &self.timeout)
+            .field("default_headers", &self.default_headers)
+            .field("user_agent", &self.user_agent)
+            .field("api_client", &self.api_client)
+            .field("project_id", &self.project_id)
+            .field("service_account_email", &self.service_account_email)
+            .field("user_project", &self.user_project)
+            .field("quota_project", &self.quota_project)
+            .field("retry_policy", &self.retry_policy)
+            .field("middleware", &self.middleware.len())
+            .field("metrics", &self.metrics.is_some())
+            .finish()
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        dotenv::dotenv().ok();
+        let builder = Self {
+            client: None,
+            token_cache: Some(sync::Arc::new(crate::Token::default())),
+            signer: sync::Arc::new(crate::ServiceAccountSigner::default()),
+            api_endpoint: crate::BASE_URL.to_string(),
+            upload_endpoint: UPLOAD_URL.to_string(),
+            connect_timeout: None,
+            timeout: None,
+            default_headers: HeaderMap::new(),
+            user_agent: None,
+            api_client: None,
+            project_id: None,
+            service_account_email: None,
+            user_project: None,
+            quota_project: None,
+            retry_policy: Default::default(),
+            middleware: Vec::new(),
+            metrics: None,
+        };
+        match std::env::var("STORAGE_EMULATOR_HOST") {
+            Ok(host) if !host.is_empty() => builder.emulator_host(&host),
+            _ => builder,
+        }
+    }
+}
+
+impl ClientBuilder {
+    /// Creates a builder for a client that talks to `https://storage.googleapis.com`, using the
+    /// default token provider. When the `STORAGE_EMULATOR_HOST` environment parameter is set, the
+    /// builder is configured for that emulator instead, see `ClientBuilder::emulator_host`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Authenticates requests with the provided refreshable token.
+    pub fn token_cache(mut self, token: impl TokenCache + Send + 'static) -> Self {
+        self.token_cache = Some(sync::Arc::new(token));
+        self
+    }
+
+    /// Does not authenticate requests at all. See [`Client::anonymous`](crate::Client::anonymous).
+    pub fn anonymous(mut self) -> Self {
+        self.token_cache = None;
+        self
+    }
+
+    /// Sets the signer that produces the signatures of signed urls.
+    pub fn signer(mut self, signer: impl Signer + 'static) -> Self {
+        self.signer = sync::Arc::new(signer);
+        self
+    }
+
+    /// Sets both the JSON API endpoint and the upload endpoint, by appending `/storage/v1` and
+    /// `/upload/storage/v1` respectively to `endpoint`. Use this for private or regional endpoints,
+    /// such as `https://storage.europe-west3.rep.googleapis.com`.
+    pub fn endpoint(self, endpoint: &str) -> Self {
+        let endpoint = endpoint.trim_end_matches('/');
+        self.api_endpoint(format!("{}/storage/v1", endpoint))
+            .upload_endpoint(format!("{}/upload/storage/v1", endpoint))
+    }
+
+    /// Configures the client for a storage emulator such as
+    /// [fake-gcs-server](https://github.com/fsouza/fake-gcs-server) running at `host`, which is
+    /// either a url or a `host:port` pair. Requests are sent to the emulator and are not
+    /// authenticated. Unless a project id is set, the project is read from the
+    /// `GOOGLE_CLOUD_PROJECT` environment parameter, falling back to `test`.
+    pub fn emulator_host(self, host: &str) -> Self {
+        let endpoint = if host.starts_with("http://") || host.starts_with("https://") {
+            host.to_string()
+        } else {
+            format!("http://{}", host)
+        };
+        let project_id = self
+            .project_id
+            .clone()
+            .or_else(|| std::env::var("GOOGLE_CLOUD_PROJECT").ok())
+            .unwrap_or_else(|| "test".to_string());
+        self.endpoint(&endpoint).anonymous().project_id(project_id)
+    }
+
+    /// Sets the project in which buckets and HMAC keys are created and listed. Defaults to the
+    /// project of the service account found in the `SERVICE_ACCOUNT` environment parameter.
+    pub fn project_id(mut self, project_id: impl Into<String>) -> Self {
+        self.project_id = Some(project_id.into());
+        self
+    }
+
+    /// Sets the service account for which `HmacKeyClient::create` creates HMAC keys. Defaults to
+    /// the service account found in the `SERVICE_ACCOUNT` environment parameter.
+    pub fn service_account_email(mut self, email: impl Into<String>) -> Self {
+        self.service_account_email = Some(email.into());
+        self
+    }
+
+    /// Sets the project that is billed for every request, which is sent as the `userProject` query
+    /// parameter. This is required to access buckets that have requester pays enabled, and can be
+    /// overridden for a single sub-client with for instance `ObjectClient::with_user_project`.
+    pub fn user_project(mut self, project: impl Into<String>) -> Self {
+        self.user_project = Some(project.into());
+        self
+    }
+
+    /// Sets the project that quota and usage are attributed to, which is sent as the
+    /// `x-goog-user-project` header. Unlike `user_project`, this does not affect billing.
+    pub fn quota_project(mut self, project: impl Into<String>) -> Self {
+        self.quota_project = Some(project.into());
+        self
+    }
+
+    /// Sets the policy that determines how failed requests are retried. Defaults to
+    /// `RetryPolicy::default()`.
+    pub fn retry_policy(mut self, retry_policy: crate::RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Adds a middleware that every request of the client passes through. Middleware is called in
+    /// the order in which it is added, see [`Middleware`](crate::Middleware).
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(sync::Arc::new(middleware));
+        self
+    }
+
+    /// Sets the hook that receives the counters and histograms of every request, see
+    /// [`Metrics`](crate::Metrics). By default no metrics are recorded.
+    pub fn metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+        self.metrics = Some(sync::Arc::new(metrics));
+        self
+    }
+
+    /// Sets the endpoint of the JSON API, which defaults to
+    /// `https://storage.googleapis.com/storage/v1`.
+    pub fn api_endpoint(mut self, api_endpoint: impl Into<String>) -> Self {
+        self.api_endpoint = api_endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the endpoint for media uploads, which defaults to
+    /// `https://storage.googleapis.com/upload/storage/v1`.
+    pub fn upload_endpoint(mut self, upload_endpoint: impl Into<String>) -> Self {
+        self.upload_endpoint = upload_endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Uses the provided HTTP client instead of building one. The `connect_timeout` and `timeout`
+    /// of the builder are not applied to this client, so those should be configured on the
+    /// client itself.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the timeout for connecting to the endpoints.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for a complete request, from connecting until the response body has been
+    /// read.
+    ///
+    /// There is no separate read timeout: the version of `reqwest` this crate is built on cannot
+    /// limit the time between two reads of a response, so this timeout is the only one that
+    /// applies once a connection has been made. Choose it long enough for the largest up- or
+    /// download the client makes, or pass a client with its own configuration to `http_client`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a header that is sent with every request.
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Sets the `User-Agent` header that is sent with every request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the `x-goog-api-client` header that is sent with every request, which defaults to
+    /// `cloud-storage-rs/{version of this crate}`.
+    pub fn api_client(mut self, api_client: impl Into<String>) -> Self {
+        self.api_client = Some(api_client.into());
+        self
+    }
+
+    /// Builds the `Client`.
+    pub fn build(mut self) -> crate::Result<crate::Client> {
+        if self.client.is_none() && (self.connect_timeout.is_some() || self.timeout.is_some()) {
+            let mut builder = reqwest::Client::builder();
+            if let Some(timeout) = self.connect_timeout {
+                builder = builder.connect_timeout(timeout);
+            }
+            if let Some(timeout) = self.timeout {
+                builder = builder.timeout(timeout);
+            }
+            self.client = Some(builder.build()?);
+        }
+        if let Some(api_client) = self.api_client.take() {
+            let api_client = api_client.parse()?;
+            self.default_headers.insert("x-goog-api-client", api_client);
+        }
+        if let Some(quota_project) = self.quota_project.take() {
+            let quota_project = quota_project.parse()?;
+            self.default_headers
+                .insert("x-goog-user-project", quota_project);
+        }
+        if let Some(user_agent) = self.user_agent.take() {
+            self.default_headers.insert(USER_AGENT, user_agent.parse()?);
+        }
+        Ok(self.build_infallible())
+    }
+
+    /// Builds the `Client` from the options that cannot fail to apply, ignoring the others.
+    /// `build` applies those first, and the constructors of `Client` that cannot fail do not set
+    /// them.
+    pub(crate) fn build_infallible(mut self) -> crate::Client {
+        self.default_headers
+            .entry("x-goog-api-client")
+            .or_insert(HeaderValue::from_static(API_CLIENT));
+        crate::Client {
+            client: self.client.unwrap_or_default(),
+            token_cache: self.token_cache,
+            refresh_lock: Default::default(),
+            signer: self.signer,
+            api_endpoint: self.api_endpoint,
+            upload_endpoint: self.upload_endpoint,
+            default_headers: self.default_headers,
+            project_id: self.project_id,
+            service_account_email: self.service_account_email,
+            user_project: self
+                .user_project
+                .map(crate::client::UserProject::new)
+                .unwrap_or_default(),
+            retry_policy: self.retry_policy,
+            middleware: self.middleware,
+            metrics: self.metrics,
+        }
+    }
+
+    /// Builds a synchronous [`Client`](crate::sync::Client).
+    #[cfg(feature = "sync")]
+    pub fn build_sync(self) -> crate::Result<crate::sync::Client> {
+        crate::sync::Client::from_client(self.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn builder() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientBuilder::new()
+            .anonymous()
+            .endpoint("http://localhost:4443/")
+            .user_agent("my-app/1.0")
+            .build()?;
+        assert_eq!(client.api_endpoint, "http://localhost:4443/storage/v1");
+        assert_eq!(
+            client.upload_endpoint,
+            "http://localhost:4443/upload/storage/v1"
+        );
+
+        let headers = client.get_headers().await?;
+        assert_eq!(headers[USER_AGENT], "my-app/1.0");
+        assert_eq!(
+            headers["x-goog-api-client"],
+            format!("cloud-storage-rs/{}", env!("CARGO_PKG_VERSION")).as_str()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn infallible_constructors() {
+        let client = crate::Client::anonymous();
+        assert!(client.token_cache.is_none());
+        assert_eq!(
+            client.default_headers["x-goog-api-client"],
+            format!("cloud-storage-rs/{}", env!("CARGO_PKG_VERSION")).as_str()
+        );
+        let client = crate::Client::with_cache(crate::Token::default());
+        assert!(client.token_cache.is_some());
+    }
+
+    #[test]
+    fn emulator_host() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientBuilder::new()
+            .project_id("my-project")
+            .service_account_email("test@my-project.iam.gserviceaccount.com")
+            .emulator_host("localhost:4443")
+            .build()?;
+        assert_eq!(client.api_endpoint, "http://localhost:4443/storage/v1");
+        assert_eq!(
+            client.upload_endpoint,
+            "http://localhost:4443/upload/storage/v1"
+        );
+        assert!(client.token_cache.is_none());
+        assert_eq!(client.project_id(), "my-project");
+        assert_eq!(
+            client.service_account_email(),
+            "test@my-project.iam.gserviceaccount.com"
+        );
+        Ok(())
+    }
+}
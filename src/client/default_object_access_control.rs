@@ -1,5 +1,6 @@
 use crate::{
     bucket_access_control::Entity,
+    client::SendExt,
     default_object_access_control::{DefaultObjectAccessControl, NewDefaultObjectAccessControl},
     error::GoogleResponse,
     object::percent_encode,
@@ -8,9 +9,20 @@ use crate::{
 
 /// Operations on [`DefaultObjectAccessControl`](DefaultObjectAccessControl)s.
 #[derive(Debug)]
-pub struct DefaultObjectAccessControlClient<'a>(pub(super) &'a super::Client);
+pub struct DefaultObjectAccessControlClient<'a>(
+    pub(super) &'a super::Client,
+    pub(super) super::UserProject,
+);
 
 impl<'a> DefaultObjectAccessControlClient<'a> {
+    /// Bills the requests made by this client to `project`, which is needed to manage the
+    /// default object access controls of a requester pays bucket. See
+    /// `BucketClient::with_user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = super::UserProject::new(project.into());
+        self
+    }
+
     /// Create a new `DefaultObjectAccessControl` entry on the specified bucket.
     /// ### Important
     /// Important: This method fails with a `400 Bad Request` response for buckets with uniform
@@ -42,7 +54,7 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
     ) -> crate::Result<DefaultObjectAccessControl> {
         let url = format!(
             "{}/b/{}/defaultObjectAcl",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket)
         );
         let result: GoogleResponse<DefaultObjectAccessControl> = self
@@ -51,7 +63,8 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
             .post(&url)
             .headers(self.0.get_headers().await?)
             .json(new_acl)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -84,7 +97,7 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
     pub async fn list(&self, bucket: &str) -> crate::Result<Vec<DefaultObjectAccessControl>> {
         let url = format!(
             "{}/b/{}/defaultObjectAcl",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket)
         );
         let result: GoogleResponse<ListResponse<DefaultObjectAccessControl>> = self
@@ -92,7 +105,8 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -137,7 +151,7 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
     ) -> crate::Result<DefaultObjectAccessControl> {
         let url = format!(
             "{}/b/{}/defaultObjectAcl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(&entity.to_string()),
         );
@@ -146,7 +160,8 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -184,7 +199,7 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
     ) -> crate::Result<DefaultObjectAccessControl> {
         let url = format!(
             "{}/b/{}/defaultObjectAcl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&default_object_access_control.bucket),
             percent_encode(&default_object_access_control.entity.to_string()),
         );
@@ -194,7 +209,8 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
             .put(&url)
             .headers(self.0.get_headers().await?)
             .json(default_object_access_control)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -231,21 +247,17 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
     ) -> Result<(), crate::Error> {
         let url = format!(
             "{}/b/{}/defaultObjectAcl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&default_object_access_control.bucket),
             percent_encode(&default_object_access_control.entity.to_string()),
         );
-        let response = self
-            .0
+        self.0
             .client
             .delete(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?;
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::Error::Google(response.json().await?))
-        }
+        Ok(())
     }
 }
@@ -1,14 +1,24 @@
 use crate::{
+    client::SendExt,
     error::GoogleResponse,
     hmac_key::{HmacKey, HmacMeta, HmacState},
 };
 
 /// Operations on [`HmacKey`](HmacKey)s.
 #[derive(Debug)]
-pub struct HmacKeyClient<'a>(pub(super) &'a super::Client);
+pub struct HmacKeyClient<'a>(pub(super) &'a super::Client, pub(super) super::UserProject);
 
 impl<'a> HmacKeyClient<'a> {
-    /// Creates a new HMAC key for the specified service account.
+    /// Bills the requests made by this client to `project` instead of the project set with
+    /// `ClientBuilder::user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = super::UserProject::new(project.into());
+        self
+    }
+
+    /// Creates a new HMAC key for the service account set with
+    /// `ClientBuilder::service_account_email`, which defaults to the service account found in the
+    /// `SERVICE_ACCOUNT` environment parameter.
     ///
     /// The authenticated user must have `storage.hmacKeys.create` permission for the project in
     /// which the key will be created.
@@ -35,10 +45,10 @@ impl<'a> HmacKeyClient<'a> {
 
         let url = format!(
             "{}/projects/{}/hmacKeys",
-            crate::BASE_URL,
-            crate::SERVICE_ACCOUNT.project_id
+            self.0.api_endpoint,
+            self.0.project_id()
         );
-        let query = [("serviceAccountEmail", &crate::SERVICE_ACCOUNT.client_email)];
+        let query = [("serviceAccountEmail", self.0.service_account_email())];
         let mut headers = self.0.get_headers().await?;
         headers.insert(CONTENT_LENGTH, 0.into());
         let result: GoogleResponse<HmacKey> = self
@@ -47,7 +57,8 @@ impl<'a> HmacKeyClient<'a> {
             .post(&url)
             .headers(headers)
             .query(&query)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -81,15 +92,16 @@ impl<'a> HmacKeyClient<'a> {
     pub async fn list(&self) -> crate::Result<Vec<HmacMeta>> {
         let url = format!(
             "{}/projects/{}/hmacKeys",
-            crate::BASE_URL,
-            crate::SERVICE_ACCOUNT.project_id
+            self.0.api_endpoint,
+            self.0.project_id()
         );
         let response = self
             .0
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .text()
             .await?;
@@ -131,8 +143,8 @@ impl<'a> HmacKeyClient<'a> {
     pub async fn read(&self, access_id: &str) -> crate::Result<HmacMeta> {
         let url = format!(
             "{}/projects/{}/hmacKeys/{}",
-            crate::BASE_URL,
-            crate::SERVICE_ACCOUNT.project_id,
+            self.0.api_endpoint,
+            self.0.project_id(),
             access_id
         );
         let result: GoogleResponse<HmacMeta> = self
@@ -140,7 +152,8 @@ impl<'a> HmacKeyClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -173,8 +186,8 @@ impl<'a> HmacKeyClient<'a> {
     pub async fn update(&self, access_id: &str, state: HmacState) -> crate::Result<HmacMeta> {
         let url = format!(
             "{}/projects/{}/hmacKeys/{}",
-            crate::BASE_URL,
-            crate::SERVICE_ACCOUNT.project_id,
+            self.0.api_endpoint,
+            self.0.project_id(),
             access_id
         );
         serde_json::to_string(&crate::hmac_key::UpdateMeta { state })?;
@@ -184,7 +197,8 @@ impl<'a> HmacKeyClient<'a> {
             .put(&url)
             .headers(self.0.get_headers().await?)
             .json(&crate::hmac_key::UpdateMeta { state })
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -216,21 +230,17 @@ impl<'a> HmacKeyClient<'a> {
     pub async fn delete(&self, access_id: &str) -> crate::Result<()> {
         let url = format!(
             "{}/projects/{}/hmacKeys/{}",
-            crate::BASE_URL,
-            crate::SERVICE_ACCOUNT.project_id,
+            self.0.api_endpoint,
+            self.0.project_id(),
             access_id
         );
-        let response = self
-            .0
+        self.0
             .client
             .delete(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?;
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::Error::Google(response.json().await?))
-        }
+        Ok(())
     }
 }
@@ -1,20 +1,75 @@
 use futures_util::{stream, Stream, TryStream};
-use reqwest::StatusCode;
+use std::collections::HashMap;
 
 use crate::{
+    client::SendExt,
     error::GoogleResponse,
-    object::{percent_encode, ComposeRequest, ObjectList, RewriteResponse, SizedByteStream},
-    ListRequest, Object,
+    object::{
+        percent_encode, ComposeRequest, ObjectList, Preconditions, RewriteResponse, SizedByteStream,
+    },
+    post_policy::{PostPolicy, PostPolicyBuilder},
+    ListRequest, Object, SignedUrlBuilder,
 };
 
-// Object uploads has its own url for some reason
-const BASE_URL: &str = "https://storage.googleapis.com/upload/storage/v1/b";
-
 /// Operations on [`Object`](Object)s.
 #[derive(Debug)]
-pub struct ObjectClient<'a>(pub(super) &'a super::Client);
+pub struct ObjectClient<'a>(
+    pub(super) &'a super::Client,
+    pub(super) Preconditions,
+    pub(super) super::UserProject,
+);
 
 impl<'a> ObjectClient<'a> {
+    /// Bills the requests made by this client to `project`, which is required to read from or
+    /// write to a bucket that has requester pays enabled. This overrides the project set with
+    /// `ClientBuilder::user_project`.
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::Client;
+    ///
+    /// let client = Client::default();
+    /// let data = client
+    ///     .object()
+    ///     .with_user_project("my-project")
+    ///     .download("requester-pays-bucket", "path/to/my/file.png")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.2 = super::UserProject::new(project.into());
+        self
+    }
+
+    /// Attaches `preconditions` to the requests made by this client, except for `list`. For
+    /// `copy`, `rewrite` and `compose` they apply to the destination object.
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::Client;
+    /// use cloud_storage::object::Preconditions;
+    ///
+    /// let client = Client::default();
+    /// let object = client.object().read("my_bucket", "path/to/my/file.png").await?;
+    /// client
+    ///     .object()
+    ///     .with_preconditions(Preconditions {
+    ///         if_generation_match: Some(object.generation),
+    ///         ..Default::default()
+    ///     })
+    ///     .delete("my_bucket", "path/to/my/file.png")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_preconditions(mut self, preconditions: Preconditions) -> Self {
+        self.1 = preconditions;
+        self
+    }
+
     /// Create a new object.
     /// Upload a file as that is loaded in memory to google cloud storage, where it will be
     /// interpreted according to the mime type you specified.
@@ -42,8 +97,8 @@ impl<'a> ObjectClient<'a> {
         use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
 
         let url = &format!(
-            "{}/{}/o?uploadType=media&name={}",
-            BASE_URL,
+            "{}/b/{}/o?uploadType=media&name={}",
+            self.0.upload_endpoint,
             percent_encode(bucket),
             percent_encode(filename),
         );
@@ -55,14 +110,12 @@ impl<'a> ObjectClient<'a> {
             .client
             .post(url)
             .headers(headers)
+            .query(&self.1)
             .body(file)
-            .send()
+            .query(&self.2)
+            .send_with(self.0)
             .await?;
-        if response.status() == 200 {
-            Ok(serde_json::from_str(&response.text().await?)?)
-        } else {
-            Err(crate::Error::new(&response.text().await?))
-        }
+        Ok(serde_json::from_str(&response.text().await?)?)
     }
 
     /// Create a new object. This works in the same way as `ObjectClient::create`, except it does not need
@@ -100,8 +153,8 @@ impl<'a> ObjectClient<'a> {
         use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
 
         let url = &format!(
-            "{}/{}/o?uploadType=media&name={}",
-            BASE_URL,
+            "{}/b/{}/o?uploadType=media&name={}",
+            self.0.upload_endpoint,
             percent_encode(bucket),
             percent_encode(filename),
         );
@@ -117,14 +170,12 @@ impl<'a> ObjectClient<'a> {
             .client
             .post(url)
             .headers(headers)
+            .query(&self.1)
             .body(body)
-            .send()
+            .query(&self.2)
+            .send_with(self.0)
             .await?;
-        if response.status() == 200 {
-            Ok(serde_json::from_str(&response.text().await?)?)
-        } else {
-            Err(crate::Error::new(&response.text().await?))
-        }
+        Ok(serde_json::from_str(&response.text().await?)?)
     }
 
     /// Obtain a list of objects within this Bucket.
@@ -168,61 +219,59 @@ impl<'a> ObjectClient<'a> {
         }
 
         let client = self.0;
+        let user_project = self.2.clone();
 
         Ok(stream::unfold(
             ListState::Start(list_request),
-            move |mut state| async move {
-                let url = format!("{}/b/{}/o", crate::BASE_URL, percent_encode(bucket));
-                let headers = match client.get_headers().await {
-                    Ok(h) => h,
-                    Err(e) => return Some((Err(e), state)),
-                };
-                let req = state.req_mut()?;
-                if req.max_results == Some(0) {
-                    return None;
-                }
-
-                let response = client
-                    .client
-                    .get(&url)
-                    .query(req)
-                    .headers(headers)
-                    .send()
-                    .await;
-
-                let response = match response {
-                    Ok(r) if r.status() == 200 => r,
-                    Ok(r) => {
-                        let e = match r.json::<crate::error::GoogleErrorResponse>().await {
-                            Ok(err_res) => err_res.into(),
-                            Err(serde_err) => serde_err.into(),
-                        };
-                        return Some((Err(e), state));
+            move |mut state| {
+                let user_project = user_project.clone();
+                async move {
+                    let url = format!("{}/b/{}/o", client.api_endpoint, percent_encode(bucket));
+                    let headers = match client.get_headers().await {
+                        Ok(h) => h,
+                        Err(e) => return Some((Err(e), state)),
+                    };
+                    let req = state.req_mut()?;
+                    if req.max_results == Some(0) {
+                        return None;
                     }
-                    Err(e) => return Some((Err(e.into()), state)),
-                };
-
-                let result: GoogleResponse<ObjectList> = match response.json().await {
-                    Ok(json) => json,
-                    Err(e) => return Some((Err(e.into()), state)),
-                };
-
-                let response_body = match result {
-                    GoogleResponse::Success(success) => success,
-                    GoogleResponse::Error(e) => return Some((Err(e.into()), state)),
-                };
-
-                let next_state = if let Some(ref page_token) = response_body.next_page_token {
-                    req.page_token = Some(page_token.clone());
-                    req.max_results = req
-                        .max_results
-                        .map(|rem| rem.saturating_sub(response_body.items.len()));
-                    state.into_has_more()?
-                } else {
-                    Done
-                };
-
-                Some((Ok(response_body), next_state))
+
+                    let response = client
+                        .client
+                        .get(&url)
+                        .query(req)
+                        .query(&user_project)
+                        .headers(headers)
+                        .send_with(client)
+                        .await;
+
+                    let response = match response {
+                        Ok(r) => r,
+                        Err(e) => return Some((Err(e), state)),
+                    };
+
+                    let result: GoogleResponse<ObjectList> = match response.json().await {
+                        Ok(json) => json,
+                        Err(e) => return Some((Err(e.into()), state)),
+                    };
+
+                    let response_body = match result {
+                        GoogleResponse::Success(success) => success,
+                        GoogleResponse::Error(e) => return Some((Err(e.into()), state)),
+                    };
+
+                    let next_state = if let Some(ref page_token) = response_body.next_page_token {
+                        req.page_token = Some(page_token.clone());
+                        req.max_results = req
+                            .max_results
+                            .map(|rem| rem.saturating_sub(response_body.items.len()));
+                        state.into_has_more()?
+                    } else {
+                        Done
+                    };
+
+                    Some((Ok(response_body), next_state))
+                }
             },
         ))
     }
@@ -243,7 +292,7 @@ impl<'a> ObjectClient<'a> {
     pub async fn read(&self, bucket: &str, file_name: &str) -> crate::Result<Object> {
         let url = format!(
             "{}/b/{}/o/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(file_name),
         );
@@ -252,7 +301,9 @@ impl<'a> ObjectClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .query(&self.2)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -278,22 +329,24 @@ impl<'a> ObjectClient<'a> {
     pub async fn download(&self, bucket: &str, file_name: &str) -> crate::Result<Vec<u8>> {
         let url = format!(
             "{}/b/{}/o/{}?alt=media",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(file_name),
         );
+        let start = std::time::Instant::now();
         let resp = self
             .0
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .query(&self.2)
+            .send_with(self.0)
             .await?;
-        if resp.status() == StatusCode::NOT_FOUND {
-            Err(crate::Error::Other(resp.text().await?))
-        } else {
-            Ok(resp.error_for_status()?.bytes().await?.to_vec())
-        }
+        let data = resp.bytes().await?.to_vec();
+        self.0
+            .record_download("storage.objects.get", data.len() as u64, start.elapsed());
+        Ok(data)
     }
 
     /// Download the content of the object with the specified name in the specified bucket, without
@@ -326,7 +379,7 @@ impl<'a> ObjectClient<'a> {
         use futures_util::{StreamExt, TryStreamExt};
         let url = format!(
             "{}/b/{}/o/{}?alt=media",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(file_name),
         );
@@ -335,9 +388,10 @@ impl<'a> ObjectClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
-            .await?
-            .error_for_status()?;
+            .query(&self.1)
+            .query(&self.2)
+            .send_with(self.0)
+            .await?;
         let size = response.content_length();
         let bytes = response
             .bytes_stream()
@@ -368,7 +422,7 @@ impl<'a> ObjectClient<'a> {
     pub async fn update(&self, object: &Object) -> crate::Result<Object> {
         let url = format!(
             "{}/b/{}/o/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&object.bucket),
             percent_encode(&object.name),
         );
@@ -377,8 +431,10 @@ impl<'a> ObjectClient<'a> {
             .client
             .put(&url)
             .headers(self.0.get_headers().await?)
+            .query(&self.1)
             .json(&object)
-            .send()
+            .query(&self.2)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -404,22 +460,19 @@ impl<'a> ObjectClient<'a> {
     pub async fn delete(&self, bucket: &str, file_name: &str) -> crate::Result<()> {
         let url = format!(
             "{}/b/{}/o/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(file_name),
         );
-        let response = self
-            .0
+        self.0
             .client
             .delete(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .query(&self.2)
+            .send_with(self.0)
             .await?;
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::Error::Google(response.json().await?))
-        }
+        Ok(())
     }
 
     /// Concatenates the contents of multiple objects into one.
@@ -462,7 +515,7 @@ impl<'a> ObjectClient<'a> {
     ) -> crate::Result<Object> {
         let url = format!(
             "{}/b/{}/o/{}/compose",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(destination_object)
         );
@@ -471,8 +524,10 @@ impl<'a> ObjectClient<'a> {
             .client
             .post(&url)
             .headers(self.0.get_headers().await?)
+            .query(&self.1)
             .json(req)
-            .send()
+            .query(&self.2)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -507,7 +562,7 @@ impl<'a> ObjectClient<'a> {
 
         let url = format!(
             "{base}/b/{sBucket}/o/{sObject}/copyTo/b/{dBucket}/o/{dObject}",
-            base = crate::BASE_URL,
+            base = self.0.api_endpoint,
             sBucket = percent_encode(&object.bucket),
             sObject = percent_encode(&object.name),
             dBucket = percent_encode(destination_bucket),
@@ -520,7 +575,9 @@ impl<'a> ObjectClient<'a> {
             .client
             .post(&url)
             .headers(headers)
-            .send()
+            .query(&self.1)
+            .query(&self.2)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -537,6 +594,7 @@ impl<'a> ObjectClient<'a> {
     /// * Geographical Location,
     /// * Encryption,
     /// * Storage class.
+    ///
     /// These limitations mean that for now, the rewrite and the copy methods do the same thing.
     /// ### Example
     /// ```no_run
@@ -562,7 +620,7 @@ impl<'a> ObjectClient<'a> {
 
         let url = format!(
             "{base}/b/{sBucket}/o/{sObject}/rewriteTo/b/{dBucket}/o/{dObject}",
-            base = crate::BASE_URL,
+            base = self.0.api_endpoint,
             sBucket = percent_encode(&object.bucket),
             sObject = percent_encode(&object.name),
             dBucket = percent_encode(destination_bucket),
@@ -575,7 +633,9 @@ impl<'a> ObjectClient<'a> {
             .client
             .post(&url)
             .headers(headers)
-            .send()
+            .query(&self.1)
+            .query(&self.2)
+            .send_with(self.0)
             .await?
             .text()
             .await?;
@@ -587,4 +647,181 @@ impl<'a> ObjectClient<'a> {
         // GoogleResponse::Error(e) => Err(e.into()),
         // }
     }
+
+    /// Signs the url built by `builder` with the [`Signer`](crate::Signer) of this client. When a
+    /// user project is set for this client, it is added to the url as the `userProject` query
+    /// parameter.
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::{Client, SignedUrlBuilder};
+    ///
+    /// let client = Client::default();
+    /// let builder = SignedUrlBuilder::new("my_bucket", "path/to/my/file.png")
+    ///     .method("DELETE")
+    ///     .duration(60);
+    /// let url = client.object().sign_url(&builder).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sign_url(&self, builder: &SignedUrlBuilder) -> crate::Result<String> {
+        match self.2.get() {
+            Some(user_project) => {
+                let builder = builder.clone().query_param("userProject", user_project);
+                builder.sign(self.0.signer()).await
+            }
+            None => builder.sign(self.0.signer()).await,
+        }
+    }
+
+    /// Signs the policy document built by `builder` with the [`Signer`](crate::Signer) of this
+    /// client, which lets a browser upload an object through an HTML form. See
+    /// [`PostPolicyBuilder`].
+    pub async fn sign_post_policy(&self, builder: &PostPolicyBuilder) -> crate::Result<PostPolicy> {
+        builder.sign(self.0.signer()).await
+    }
+
+    /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
+    /// which is valid for `duration` seconds, and lets the posessor download the contents of the
+    /// object `file_name` in `bucket` without any authentication. Unlike `download_url`, this
+    /// does not require the `Object` to be read first.
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::Client;
+    ///
+    /// let client = Client::default();
+    /// let url = client
+    ///     .object()
+    ///     .signed_download_url("my_bucket", "path/to/my/file.png", 50)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn signed_download_url(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        duration: u32,
+    ) -> crate::Result<String> {
+        let builder = SignedUrlBuilder::new(bucket, file_name).duration(duration);
+        self.sign_url(&self.on_endpoint(builder)).await
+    }
+
+    /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
+    /// which is valid for `duration` seconds, and lets the posessor upload the object `file_name`
+    /// to `bucket` with a `PUT` request, whether or not that object exists already.
+    pub async fn signed_upload_url(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        duration: u32,
+    ) -> crate::Result<String> {
+        let builder = SignedUrlBuilder::new(bucket, file_name)
+            .method("PUT")
+            .duration(duration);
+        self.sign_url(&self.on_endpoint(builder)).await
+    }
+
+    /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
+    /// which is valid for `duration` seconds, and lets the posessor start a resumable upload of
+    /// the object `file_name` to `bucket` with the content type `mime_type`. Returns the url,
+    /// together with the headers that must be included in the `POST` request. See
+    /// [`SignedUrlBuilder::resumable_upload`].
+    pub async fn signed_resumable_upload_url(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        duration: u32,
+        mime_type: &str,
+    ) -> crate::Result<(String, HashMap<String, String>)> {
+        let builder = SignedUrlBuilder::resumable_upload(bucket, file_name)
+            .duration(duration)
+            .content_type(mime_type);
+        let builder = self.on_endpoint(builder);
+        let url = self.sign_url(&builder).await?;
+        let headers = builder
+            .headers()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Ok((url, headers))
+    }
+
+    /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
+    /// which is valid for `duration` seconds, and lets the posessor download the file contents
+    /// without any authentication. The url is signed with the
+    /// [`Signer`](crate::Signer) of this client.
+    /// ### Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::{Client, IamSigner};
+    ///
+    /// let client = Client::default()
+    ///     .with_signer(IamSigner::new("signer@my-project.iam.gserviceaccount.com"));
+    /// let obj1 = client.object().read("my_bucket", "file1").await?;
+    /// let url = client.object().download_url(&obj1, 50).await?;
+    /// // url is now a url to which an unauthenticated user can make a request to download a file
+    /// // for 50 seconds.
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_url(&self, object: &Object, duration: u32) -> crate::Result<String> {
+        let builder = object.signed_url(duration, "GET", None, &HashMap::new());
+        self.sign_url(&self.on_endpoint(builder)).await
+    }
+
+    /// The same as [`ObjectClient::download_url`], but with additional options for the download.
+    pub async fn download_url_with(
+        &self,
+        object: &Object,
+        duration: u32,
+        opts: crate::DownloadOptions,
+    ) -> crate::Result<String> {
+        let builder = object.signed_url(duration, "GET", opts.content_disposition, &HashMap::new());
+        self.sign_url(&self.on_endpoint(builder)).await
+    }
+
+    /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
+    /// which is valid for `duration` seconds, and lets the posessor upload data to a blob
+    /// without any authentication. The url is signed with the
+    /// [`Signer`](crate::Signer) of this client.
+    pub async fn upload_url(&self, object: &Object, duration: u32) -> crate::Result<String> {
+        let builder = object.signed_url(duration, "PUT", None, &HashMap::new());
+        self.sign_url(&self.on_endpoint(builder)).await
+    }
+
+    /// The same as [`ObjectClient::upload_url`], but also lets the posessor set custom metadata.
+    /// Returns the url, together with the headers that must be included in the `PUT` request.
+    pub async fn upload_url_with(
+        &self,
+        object: &Object,
+        duration: u32,
+        custom_metadata: HashMap<String, String>,
+    ) -> crate::Result<(String, HashMap<String, String>)> {
+        let builder = object.signed_url(duration, "PUT", None, &custom_metadata);
+        let url = self.sign_url(&self.on_endpoint(builder)).await?;
+        let headers = custom_metadata
+            .into_iter()
+            .map(|(k, v)| (format!("x-goog-meta-{}", k), v))
+            .collect();
+        Ok((url, headers))
+    }
+
+    /// Points the url built by `builder` at the host of the endpoint of the client, such as an
+    /// emulator or a regional endpoint, instead of at `storage.googleapis.com`.
+    fn on_endpoint(&self, builder: SignedUrlBuilder) -> SignedUrlBuilder {
+        let endpoint = match reqwest::Url::parse(&self.0.xml_endpoint()) {
+            Ok(endpoint) => endpoint,
+            Err(_) => return builder,
+        };
+        let host = match (endpoint.host_str(), endpoint.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return builder,
+        };
+        builder.scheme(endpoint.scheme()).host(host)
+    }
 }
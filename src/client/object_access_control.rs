@@ -1,5 +1,6 @@
 use crate::{
     bucket_access_control::Entity,
+    client::SendExt,
     error::GoogleResponse,
     object::percent_encode,
     object_access_control::{NewObjectAccessControl, ObjectAccessControl},
@@ -8,9 +9,19 @@ use crate::{
 
 /// Operations on [`ObjectAccessControl`](ObjectAccessControl)s.
 #[derive(Debug)]
-pub struct ObjectAccessControlClient<'a>(pub(super) &'a super::Client);
+pub struct ObjectAccessControlClient<'a>(
+    pub(super) &'a super::Client,
+    pub(super) super::UserProject,
+);
 
 impl<'a> ObjectAccessControlClient<'a> {
+    /// Bills the requests made by this client to `project`, which is needed to manage the access
+    /// controls of objects in a requester pays bucket. See `ObjectClient::with_user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = super::UserProject::new(project.into());
+        self
+    }
+
     /// Creates a new ACL entry on the specified `object`.
     ///
     /// ### Important
@@ -25,7 +36,7 @@ impl<'a> ObjectAccessControlClient<'a> {
     ) -> crate::Result<ObjectAccessControl> {
         let url = format!(
             "{}/b/{}/o/{}/acl",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(object),
         );
@@ -35,7 +46,8 @@ impl<'a> ObjectAccessControlClient<'a> {
             .post(&url)
             .headers(self.0.get_headers().await?)
             .json(new_object_access_control)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -58,7 +70,7 @@ impl<'a> ObjectAccessControlClient<'a> {
     ) -> crate::Result<Vec<ObjectAccessControl>> {
         let url = format!(
             "{}/b/{}/o/{}/acl",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(object),
         );
@@ -67,7 +79,8 @@ impl<'a> ObjectAccessControlClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -91,7 +104,7 @@ impl<'a> ObjectAccessControlClient<'a> {
     ) -> crate::Result<ObjectAccessControl> {
         let url = format!(
             "{}/b/{}/o/{}/acl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(bucket),
             percent_encode(object),
             percent_encode(&entity.to_string())
@@ -101,7 +114,8 @@ impl<'a> ObjectAccessControlClient<'a> {
             .client
             .get(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -123,7 +137,7 @@ impl<'a> ObjectAccessControlClient<'a> {
     ) -> crate::Result<ObjectAccessControl> {
         let url = format!(
             "{}/b/{}/o/{}/acl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&object_access_control.bucket),
             percent_encode(&object_access_control.object),
             percent_encode(&object_access_control.entity.to_string()),
@@ -134,7 +148,8 @@ impl<'a> ObjectAccessControlClient<'a> {
             .put(&url)
             .headers(self.0.get_headers().await?)
             .json(object_access_control)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?
             .json()
             .await?;
@@ -153,22 +168,18 @@ impl<'a> ObjectAccessControlClient<'a> {
     pub async fn delete(&self, object_access_control: ObjectAccessControl) -> crate::Result<()> {
         let url = format!(
             "{}/b/{}/o/{}/acl/{}",
-            crate::BASE_URL,
+            self.0.api_endpoint,
             percent_encode(&object_access_control.bucket),
             percent_encode(&object_access_control.object),
             percent_encode(&object_access_control.entity.to_string()),
         );
-        let response = self
-            .0
+        self.0
             .client
             .delete(&url)
             .headers(self.0.get_headers().await?)
-            .send()
+            .query(&self.1)
+            .send_with(self.0)
             .await?;
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::Error::Google(response.json().await?))
-        }
+        Ok(())
     }
 }
@@ -0,0 +1,105 @@
+//! The cryptographic primitives used for signing, backed by either openssl or ring, depending on
+//! the enabled features.
+
+#[cfg(feature = "openssl")]
+mod openssl {
+    #[inline(always)]
+    pub fn rsa_pkcs1_sha256(private_key: &str, message: &[u8]) -> crate::Result<Vec<u8>> {
+        use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+
+        let key = PKey::private_key_from_pem(private_key.as_bytes())?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+        signer.update(message)?;
+        Ok(signer.sign_to_vec()?)
+    }
+
+    #[inline(always)]
+    pub fn sha256(bytes: &[u8]) -> impl AsRef<[u8]> {
+        openssl::sha::sha256(bytes)
+    }
+
+    #[inline(always)]
+    pub fn hmac_sha256(key: &[u8], message: &[u8]) -> crate::Result<Vec<u8>> {
+        use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+
+        let key = PKey::hmac(key)?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+        signer.update(message)?;
+        Ok(signer.sign_to_vec()?)
+    }
+}
+
+#[cfg(feature = "ring")]
+mod ring {
+    #[cfg_attr(all(feature = "ring", feature = "openssl"), allow(dead_code))]
+    #[inline(always)]
+    pub fn rsa_pkcs1_sha256(private_key: &str, message: &[u8]) -> crate::Result<Vec<u8>> {
+        use ring::{
+            rand::SystemRandom,
+            signature::{RsaKeyPair, RSA_PKCS1_SHA256},
+        };
+
+        let key_pem = pem::parse(private_key.as_bytes())?;
+        let key = RsaKeyPair::from_pkcs8(&key_pem.contents)?;
+        let rng = SystemRandom::new();
+        let mut signature = vec![0; key.public_modulus_len()];
+        key.sign(&RSA_PKCS1_SHA256, &rng, message, &mut signature)?;
+        Ok(signature)
+    }
+
+    #[cfg_attr(all(feature = "ring", feature = "openssl"), allow(dead_code))]
+    #[inline(always)]
+    pub fn sha256(bytes: &[u8]) -> impl AsRef<[u8]> {
+        use ring::digest::{digest, SHA256};
+        digest(&SHA256, bytes)
+    }
+
+    #[cfg_attr(all(feature = "ring", feature = "openssl"), allow(dead_code))]
+    #[inline(always)]
+    pub fn hmac_sha256(key: &[u8], message: &[u8]) -> crate::Result<Vec<u8>> {
+        use ring::hmac::{sign, Key, HMAC_SHA256};
+        Ok(sign(&Key::new(HMAC_SHA256, key), message).as_ref().to_vec())
+    }
+}
+
+#[cfg(feature = "openssl")]
+pub(crate) use self::openssl::*;
+#[cfg(all(feature = "ring", not(feature = "openssl")))]
+pub(crate) use self::ring::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac() {
+        // test case 2 of RFC 4231
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?").unwrap();
+        assert_eq!(
+            hex::encode(mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[cfg(all(feature = "openssl", feature = "ring"))]
+    #[test]
+    fn check_matching_crypto() {
+        use crate::resources::service_account::ServiceAccount;
+
+        let key = ServiceAccount::test().private_key;
+        assert_eq!(
+            openssl::sha256(b"hello").as_ref(),
+            ring::sha256(b"hello").as_ref()
+        );
+
+        assert_eq!(
+            openssl::rsa_pkcs1_sha256(&key, b"world").unwrap(),
+            ring::rsa_pkcs1_sha256(&key, b"world").unwrap(),
+        );
+
+        assert_eq!(
+            openssl::hmac_sha256(b"key", b"world").unwrap(),
+            ring::hmac_sha256(b"key", b"world").unwrap(),
+        );
+    }
+}
@@ -1,7 +1,11 @@
 /// Represents any of the ways storing something in Google Cloud Storage can fail.
 #[derive(Debug)]
 pub enum Error {
-    /// If the error is caused by a non 2xx response by Google, this variant is returned.
+    /// If the error is caused by a non 2xx response by Google, or by an XML error in the body of a
+    /// successful response, this variant is returned.
+    Http(Box<HttpError>),
+    /// An error response by Google without the HTTP context it was received in. Methods of
+    /// `Client` return `Error::Http` instead.
     Google(GoogleErrorResponse),
     /// If another network error causes something to fail, this variant is used.
     Reqwest(reqwest::Error),
@@ -21,6 +25,8 @@ pub enum Error {
     Jwt(jsonwebtoken::errors::Error),
     /// If we cannot deserialize one of the repsonses sent by Google, this variant is used.
     Serialization(serde_json::error::Error),
+    /// If reading or writing data fails, this variant is used.
+    Io(std::io::Error),
     /// If another failure causes the error, this variant is populated.
     Other(String),
 }
@@ -29,6 +35,120 @@ impl Error {
     pub(crate) fn new(msg: &str) -> Error {
         Error::Other(msg.to_string())
     }
+
+    /// The HTTP status code of the response that caused this error, if the error was caused by a
+    /// response.
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Self::Http(e) => Some(e.status),
+            Self::Google(e) => reqwest::StatusCode::from_u16(e.error.code).ok(),
+            Self::Reqwest(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// The error response sent by Google, if there is one.
+    pub fn google_response(&self) -> Option<&GoogleErrorResponse> {
+        match self {
+            Self::Http(e) => e.google.as_ref(),
+            Self::Google(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the requested resource does not exist.
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(reqwest::StatusCode::NOT_FOUND)
+    }
+
+    /// Returns whether one of the preconditions of the request, such as `ifGenerationMatch`, did
+    /// not hold.
+    pub fn is_precondition_failed(&self) -> bool {
+        self.status() == Some(reqwest::StatusCode::PRECONDITION_FAILED)
+    }
+
+    /// Returns whether the request may succeed when it is sent again, because it failed to
+    /// connect, timed out, was rate limited or ran into a server error.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Reqwest(e) if crate::retry::is_retryable_error(e) => true,
+            _ => self.status().is_some_and(crate::retry::is_retryable_status),
+        }
+    }
+}
+
+/// A non 2xx response by Google, together with the context it was received in. The XML API also
+/// reports some errors, such as those of completing a multipart upload, in the body of a 200
+/// response, which are returned as an `HttpError` as well.
+#[derive(Debug)]
+pub struct HttpError {
+    /// The HTTP status code of the response.
+    pub status: reqwest::StatusCode,
+    /// The headers of the response.
+    pub headers: reqwest::header::HeaderMap,
+    /// The error response, if the body could be parsed as one.
+    pub google: Option<GoogleErrorResponse>,
+    /// The error response of the XML API, if the body could be parsed as one.
+    #[cfg(feature = "xml")]
+    pub xml: Option<crate::xml::XmlErrorResponse>,
+    /// The body of the response.
+    pub body: String,
+}
+
+impl HttpError {
+    /// Reads the body of `response`, which should have a non 2xx status code.
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await.unwrap_or_default();
+        let google = serde_json::from_str(&body).ok();
+        #[cfg(feature = "xml")]
+        let xml = match google {
+            Some(_) => None,
+            None => crate::xml::XmlErrorResponse::parse(&body),
+        };
+        Self {
+            status,
+            headers,
+            google,
+            #[cfg(feature = "xml")]
+            xml,
+            body,
+        }
+    }
+
+    /// The id Google assigned to the request, taken from the `x-guploader-uploadid` header. Useful
+    /// when reporting issues to Google.
+    pub fn request_id(&self) -> Option<&str> {
+        self.headers
+            .get("x-guploader-uploadid")
+            .and_then(|id| id.to_str().ok())
+    }
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(google) = &self.google {
+            return write!(f, "{}: {}", self.status, google.error.message);
+        }
+        #[cfg(feature = "xml")]
+        if let Some(xml) = &self.xml {
+            return write!(f, "{}: {}", self.status, xml);
+        }
+        write!(f, "{}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for HttpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        #[cfg(feature = "xml")]
+        if let Some(xml) = &self.xml {
+            return Some(xml);
+        }
+        self.google
+            .as_ref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -40,6 +160,7 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
+            Self::Http(e) => Some(e),
             Self::Google(e) => Some(e),
             Self::Reqwest(e) => Some(e),
             #[cfg(feature = "openssl")]
@@ -52,6 +173,7 @@ impl std::error::Error for Error {
             Self::Signing(e) => Some(e),
             Self::Jwt(e) => Some(e),
             Self::Serialization(e) => Some(e),
+            Self::Io(e) => Some(e),
             Self::Other(_) => None,
         }
     }
@@ -111,7 +233,7 @@ impl From<reqwest::header::InvalidHeaderValue> for Error {
 
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
-        Self::Other(err.to_string())
+        Self::Io(err)
     }
 }
 
@@ -231,14 +353,21 @@ impl GoogleError {
     }
 }
 
+impl From<HttpError> for Error {
+    fn from(err: HttpError) -> Self {
+        Self::Http(Box::new(err))
+    }
+}
+
 impl From<GoogleErrorResponse> for Error {
     fn from(err: GoogleErrorResponse) -> Self {
         Self::Google(err)
     }
 }
 
-/// Google provides a list of codes, but testing indicates that this list is not exhaustive.
-#[derive(Debug, PartialEq, serde::Deserialize)]
+/// Google provides a list of codes, but testing indicates that this list is not exhaustive. Codes
+/// that are not in this list are deserialized as `Reason::Unknown`.
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Reason {
     /// When requesting a download using alt=media URL parameter, the direct URL path to use is
@@ -457,24 +586,32 @@ pub enum Reason {
     /// May be returned by Google, meaning undocumented.
     // NONEXHAUST
     GatewayTimeout,
+    /// A reason that is not known to this crate.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Debug, serde::Deserialize)]
-#[serde(rename = "camelCase")]
-enum BadRequest {}
-
-#[derive(Debug, serde::Deserialize)]
-#[serde(rename = "camelCase")]
-enum Unauthorized {}
-
-#[derive(Debug, serde::Deserialize)]
-#[serde(rename = "camelCase")]
-enum Forbidden {}
-
-#[derive(Debug, serde::Deserialize)]
-#[serde(rename = "camelCase")]
-enum PreconditionFailed {}
-
-#[derive(Debug, serde::Deserialize)]
-#[serde(rename = "camelCase")]
-enum InternalServerError {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_reason() -> Result<(), Box<dyn std::error::Error>> {
+        let body = r#"{
+            "error": {
+                "errors": [
+                    { "domain": "global", "reason": "notFound", "message": "Not Found" },
+                    { "domain": "global", "reason": "somethingNew", "message": "New" }
+                ],
+                "code": 404,
+                "message": "Not Found"
+            }
+        }"#;
+        let response: GoogleErrorResponse = serde_json::from_str(body)?;
+        assert!(response.errors_has_reason(&Reason::NotFound));
+        let unknown = Reason::Unknown("somethingNew".to_string());
+        assert!(response.errors_has_reason(&unknown));
+        assert_eq!(serde_json::to_string(&unknown)?, r#""somethingNew""#);
+        Ok(())
+    }
+}
@@ -89,27 +89,44 @@
 #![forbid(unsafe_code, missing_docs)]
 
 pub mod client;
+pub mod metrics;
+pub mod middleware;
+pub mod post_policy;
+pub mod signed_url;
+pub mod signer;
+pub mod storage;
 #[cfg(feature = "sync")]
 pub mod sync;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "xml")]
+pub mod xml;
 
+mod crypto;
 mod download_options;
 mod error;
 /// Contains objects as represented by Google, to be used for serialization and deserialization.
 mod resources;
+mod retry;
 mod token;
 
 use crate::resources::service_account::ServiceAccount;
 pub use crate::{
-    client::Client,
+    client::{Client, ClientBuilder},
     error::*,
     resources::{
         bucket::{Bucket, NewBucket},
         object::{ListRequest, Object},
         *,
     },
-    token::{Token, TokenCache},
+    signed_url::{ParsedSignedUrl, SignatureVersion, SignedUrlBuilder, UrlStyle},
+    signer::{HmacSigner, IamSigner, ServiceAccountSigner, Signer, SigningAlgorithm},
+    token::{MetadataToken, SelfSignedToken, Token, TokenCache},
 };
 pub use download_options::DownloadOptions;
+pub use metrics::Metrics;
+pub use middleware::Middleware;
+pub use retry::RetryPolicy;
 use tokio::sync::Mutex;
 
 lazy_static::lazy_static! {
@@ -123,11 +140,33 @@ lazy_static::lazy_static! {
     pub static ref SERVICE_ACCOUNT: ServiceAccount = ServiceAccount::get();
 }
 
-#[cfg(feature = "global-client")]
+#[cfg(all(feature = "global-client", not(test)))]
 lazy_static::lazy_static! {
     static ref CLOUD_CLIENT: client::Client = client::Client::default();
 }
 
+// The tests run against the emulator in `STORAGE_EMULATOR_HOST` if it is set, and against an
+// in-process fake otherwise. They sign urls and create HMAC keys for a service account that only
+// exists for them, so that they do not require credentials.
+#[cfg(all(feature = "global-client", test))]
+lazy_static::lazy_static! {
+    static ref TEST_SERVER: testing::FakeServer = testing::FakeServer::start().unwrap();
+
+    static ref CLOUD_CLIENT: client::Client = {
+        dotenv::dotenv().ok();
+        let builder = match std::env::var("STORAGE_EMULATOR_HOST") {
+            Ok(host) if !host.is_empty() => client::Client::builder(),
+            _ => TEST_SERVER.client_builder(),
+        };
+        let service_account = ServiceAccount::test();
+        builder
+            .service_account_email(&service_account.client_email)
+            .signer(ServiceAccountSigner::new(service_account))
+            .build()
+            .unwrap()
+    };
+}
+
 /// A type alias where the error is set to be `cloud_storage::Error`.
 pub type Result<T> = std::result::Result<T, crate::Error>;
 
@@ -169,10 +208,32 @@ fn read_test_bucket_sync() -> Bucket {
     crate::runtime().unwrap().block_on(read_test_bucket())
 }
 
+// The name of the bucket that the tests use, which is read from the `TEST_BUCKET` environment
+// parameter so that it can be changed when the name is taken on the emulator.
 #[cfg(all(test, feature = "global-client"))]
-async fn read_test_bucket() -> Bucket {
+fn test_bucket_name() -> String {
     dotenv::dotenv().ok();
-    let name = std::env::var("TEST_BUCKET").unwrap();
+    std::env::var("TEST_BUCKET").unwrap_or_else(|_| "cloud-storage-rs-test".to_string())
+}
+
+// Reads a file of Google's conformance tests, which `test-data/conformance/fetch.sh` downloads.
+#[cfg(test)]
+fn read_conformance_file(name: &str) -> String {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("test-data/conformance")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {}, run test-data/conformance/fetch.sh first",
+            path.display(),
+            e
+        )
+    })
+}
+
+#[cfg(all(test, feature = "global-client"))]
+async fn read_test_bucket() -> Bucket {
+    let name = test_bucket_name();
     match Bucket::read(&name).await {
         Ok(bucket) => bucket,
         Err(_not_found) => Bucket::create(&NewBucket {
@@ -195,11 +256,7 @@ fn create_test_bucket_sync(name: &str) -> Bucket {
 // the same name in each test.
 #[cfg(all(test, feature = "global-client"))]
 async fn create_test_bucket(name: &str) -> Bucket {
-    std::thread::sleep(std::time::Duration::from_millis(1500)); // avoid getting rate limited
-
-    dotenv::dotenv().ok();
-    let base_name = std::env::var("TEST_BUCKET").unwrap();
-    let name = format!("{}-{}", base_name, name);
+    let name = format!("{}-{}", test_bucket_name(), name);
     let new_bucket = NewBucket {
         name,
         ..NewBucket::default()
@@ -0,0 +1,382 @@
+//! Counters and histograms that describe the requests sent by a [`Client`](crate::Client).
+
+use std::time::Duration;
+
+/// The number of requests, labeled with `operation` and `status`. Requests that failed without a
+/// response have the status `error`.
+pub const REQUESTS: &str = "cloud_storage_requests_total";
+/// The number of retried attempts, labeled with `operation`.
+pub const RETRIES: &str = "cloud_storage_retries_total";
+/// The time in seconds from sending a request until its response arrived, including retries,
+/// labeled with `operation` and `status`.
+pub const REQUEST_DURATION: &str = "cloud_storage_request_duration_seconds";
+/// The number of bytes sent in request bodies of a known length, labeled with `operation`.
+pub const BYTES_SENT: &str = "cloud_storage_bytes_sent_total";
+/// The number of bytes received in response bodies of a known length, labeled with `operation`.
+pub const BYTES_RECEIVED: &str = "cloud_storage_bytes_received_total";
+/// The transfer rate in bytes per second of uploads and of downloads into memory, labeled with
+/// `operation` and `direction`, which is either `upload` or `download`.
+pub const THROUGHPUT: &str = "cloud_storage_throughput_bytes_per_second";
+
+/// Receives the metrics of a [`Client`](crate::Client), for example to export them to Prometheus.
+/// A hook is registered through `ClientBuilder::metrics`, and the names of the metrics it receives
+/// are the constants of this module. Operations are named after the JSON API, for instance
+/// `storage.objects.get`.
+///
+/// ### Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use cloud_storage::{Client, Metrics};
+///
+/// struct Print;
+///
+/// impl Metrics for Print {
+///     fn increment_counter(
+///         &self,
+///         name: &'static str,
+///         value: u64,
+///         labels: &[(&'static str, &str)],
+///     ) {
+///         println!("{} {:?} += {}", name, labels, value);
+///     }
+///
+///     fn record_histogram(
+///         &self,
+///         name: &'static str,
+///         value: f64,
+///         labels: &[(&'static str, &str)],
+///     ) {
+///         println!("{} {:?} <- {}", name, labels, value);
+///     }
+/// }
+///
+/// let client = Client::builder().metrics(Print).build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait Metrics: Send + Sync {
+    /// Adds `value` to the counter `name`.
+    fn increment_counter(&self, name: &'static str, value: u64, labels: &[(&'static str, &str)]);
+
+    /// Records `value` in the histogram `name`.
+    fn record_histogram(&self, name: &'static str, value: f64, labels: &[(&'static str, &str)]);
+}
+
+/// The operation a request performs, derived from its method and url.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Operation {
+    pub(crate) name: &'static str,
+    pub(crate) bucket: Option<String>,
+    pub(crate) object: Option<String>,
+}
+
+impl Operation {
+    /// Describes `request`, which is sent to one of `endpoints`.
+    pub(crate) fn of(request: &reqwest::Request, endpoints: &[&str]) -> Self {
+        let url = request.url().as_str();
+        let path = endpoints
+            .iter()
+            .find_map(|endpoint| url.strip_prefix(*endpoint))
+            .unwrap_or_else(|| request.url().path());
+        let path = path.split(&['?', '#'][..]).next().unwrap_or("");
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                percent_encoding::percent_decode_str(s)
+                    .decode_utf8_lossy()
+                    .into_owned()
+            })
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        let method = request.method().as_str();
+        let (name, bucket, object) = match segments.as_slice() {
+            ["b"] => (collection("buckets", method, false), None, None),
+            ["b", b] => (collection("buckets", method, true), Some(b), None),
+            ["b", b, "iam"] if method == "GET" => ("storage.buckets.getIamPolicy", Some(b), None),
+            ["b", b, "iam"] => ("storage.buckets.setIamPolicy", Some(b), None),
+            ["b", b, "iam", "testPermissions"] => {
+                ("storage.buckets.testIamPermissions", Some(b), None)
+            }
+            ["b", b, "acl", rest @ ..] => (
+                collection("bucketAccessControls", method, !rest.is_empty()),
+                Some(b),
+                None,
+            ),
+            ["b", b, "defaultObjectAcl", rest @ ..] => (
+                collection("defaultObjectAccessControls", method, !rest.is_empty()),
+                Some(b),
+                None,
+            ),
+            ["b", b, "o"] => (collection("objects", method, false), Some(b), None),
+            ["b", b, "o", o] => (collection("objects", method, true), Some(b), Some(o)),
+            ["b", b, "o", o, "compose"] => ("storage.objects.compose", Some(b), Some(o)),
+            ["b", b, "o", o, "copyTo", ..] => ("storage.objects.copy", Some(b), Some(o)),
+            ["b", b, "o", o, "rewriteTo", ..] => ("storage.objects.rewrite", Some(b), Some(o)),
+            ["b", b, "o", o, "acl", rest @ ..] => (
+                collection("objectAccessControls", method, !rest.is_empty()),
+                Some(b),
+                Some(o),
+            ),
+            ["projects", _, "hmacKeys", rest @ ..] => {
+                (collection("hmacKeys", method, !rest.is_empty()), None, None)
+            }
+            ["projects", _, "serviceAccount"] => {
+                ("storage.projects.serviceAccount.get", None, None)
+            }
+            _ => ("unknown", None, None),
+        };
+        Self {
+            name,
+            bucket: bucket.map(|b| b.to_string()),
+            object: object.map(|o| o.to_string()),
+        }
+    }
+}
+
+/// Returns the name of the operation `method` performs on a collection of `resource`, or on a
+/// single item of it when `item` is true.
+fn collection(resource: &str, method: &str, item: bool) -> &'static str {
+    macro_rules! names {
+        ($($resource:literal),*) => {
+            match (resource, method, item) {
+                $(
+                    ($resource, "GET", false) => concat!("storage.", $resource, ".list"),
+                    ($resource, "POST", false) => concat!("storage.", $resource, ".insert"),
+                    ($resource, "GET", true) => concat!("storage.", $resource, ".get"),
+                    ($resource, "PUT", true) => concat!("storage.", $resource, ".update"),
+                    ($resource, "PATCH", true) => concat!("storage.", $resource, ".patch"),
+                    ($resource, "DELETE", true) => concat!("storage.", $resource, ".delete"),
+                )*
+                _ => "unknown",
+            }
+        };
+    }
+    names!(
+        "buckets",
+        "bucketAccessControls",
+        "defaultObjectAccessControls",
+        "objects",
+        "objectAccessControls",
+        "hmacKeys"
+    )
+}
+
+/// Returns the transfer rate of `bytes` in `elapsed`, or `None` when either is zero.
+pub(crate) fn throughput(bytes: u64, elapsed: Duration) -> Option<f64> {
+    let seconds = elapsed.as_secs_f64();
+    if bytes == 0 || seconds == 0.0 {
+        None
+    } else {
+        Some(bytes as f64 / seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    fn operation(method: &str, url: &str) -> Operation {
+        let request = reqwest::Client::new()
+            .request(method.parse().unwrap(), url)
+            .build()
+            .unwrap();
+        Operation::of(
+            &request,
+            &[
+                "https://storage.googleapis.com/upload/storage/v1",
+                "https://storage.googleapis.com/storage/v1",
+            ],
+        )
+    }
+
+    #[test]
+    fn operations() {
+        let api = "https://storage.googleapis.com/storage/v1";
+        let upload = "https://storage.googleapis.com/upload/storage/v1";
+        let cases = [
+            (
+                "GET",
+                format!("{}/b/?project=p", api),
+                "storage.buckets.list",
+                None,
+                None,
+            ),
+            (
+                "DELETE",
+                format!("{}/b/my-bucket", api),
+                "storage.buckets.delete",
+                Some("my-bucket"),
+                None,
+            ),
+            (
+                "PUT",
+                format!("{}/b/my-bucket/iam", api),
+                "storage.buckets.setIamPolicy",
+                Some("my-bucket"),
+                None,
+            ),
+            (
+                "GET",
+                format!("{}/b/my-bucket/o?prefix=a", api),
+                "storage.objects.list",
+                Some("my-bucket"),
+                None,
+            ),
+            (
+                "GET",
+                format!("{}/b/my-bucket/o/dir%2Ffile.txt?alt=media", api),
+                "storage.objects.get",
+                Some("my-bucket"),
+                Some("dir/file.txt"),
+            ),
+            (
+                "POST",
+                format!("{}/b/my-bucket/o?uploadType=media&name=a", upload),
+                "storage.objects.insert",
+                Some("my-bucket"),
+                None,
+            ),
+            (
+                "POST",
+                format!("{}/b/my-bucket/o/a/compose", api),
+                "storage.objects.compose",
+                Some("my-bucket"),
+                Some("a"),
+            ),
+            (
+                "POST",
+                format!("{}/b/my-bucket/o/a/rewriteTo/b/other/o/b", api),
+                "storage.objects.rewrite",
+                Some("my-bucket"),
+                Some("a"),
+            ),
+            (
+                "PATCH",
+                format!("{}/b/my-bucket/o/a/acl/allUsers", api),
+                "storage.objectAccessControls.patch",
+                Some("my-bucket"),
+                Some("a"),
+            ),
+            (
+                "GET",
+                format!("{}/projects/p/hmacKeys", api),
+                "storage.hmacKeys.list",
+                None,
+                None,
+            ),
+            (
+                "GET",
+                "https://example.com/other".to_string(),
+                "unknown",
+                None,
+                None,
+            ),
+        ];
+        for (method, url, name, bucket, object) in cases.iter() {
+            let operation = operation(method, url);
+            assert_eq!(operation.name, *name, "{} {}", method, url);
+            assert_eq!(operation.bucket.as_deref(), *bucket, "{} {}", method, url);
+            assert_eq!(operation.object.as_deref(), *object, "{} {}", method, url);
+        }
+    }
+
+    type Labels = Vec<(&'static str, String)>;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(&'static str, f64, Labels)>>);
+
+    impl Recorder {
+        fn push(&self, name: &'static str, value: f64, labels: &[(&'static str, &str)]) {
+            let labels = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+            self.0.lock().unwrap().push((name, value, labels));
+        }
+    }
+
+    impl Metrics for Arc<Recorder> {
+        fn increment_counter(
+            &self,
+            name: &'static str,
+            value: u64,
+            labels: &[(&'static str, &str)],
+        ) {
+            self.push(name, value as f64, labels);
+        }
+
+        fn record_histogram(
+            &self,
+            name: &'static str,
+            value: f64,
+            labels: &[(&'static str, &str)],
+        ) {
+            self.push(name, value, labels);
+        }
+    }
+
+    // Fails the first attempt with a 503 and answers the second with a body of 5 bytes.
+    #[derive(Default)]
+    struct Flaky(AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl crate::Middleware for Flaky {
+        async fn handle(
+            &self,
+            _request: reqwest::Request,
+            _next: crate::middleware::Next<'_>,
+        ) -> crate::Result<reqwest::Response> {
+            let status = match self.0.fetch_add(1, Ordering::SeqCst) {
+                0 => 503,
+                _ => 200,
+            };
+            let response = http::Response::builder()
+                .status(status)
+                .header("content-length", "5")
+                .body("hello")
+                .unwrap();
+            Ok(response.into())
+        }
+    }
+
+    #[tokio::test]
+    async fn metrics() -> Result<(), Box<dyn std::error::Error>> {
+        let recorder = Arc::new(Recorder::default());
+        let client = crate::Client::builder()
+            .anonymous()
+            .endpoint("http://storage.invalid")
+            .retry_policy(crate::RetryPolicy::new().initial_backoff(Duration::from_millis(1)))
+            .middleware(Flaky::default())
+            .metrics(recorder.clone())
+            .build()?;
+        client.object().download("my-bucket", "file.txt").await?;
+
+        let recorded = recorder.0.lock().unwrap();
+        let names: Vec<_> = recorded.iter().map(|(name, _, _)| *name).collect();
+        assert_eq!(
+            names,
+            [
+                REQUESTS,
+                REQUEST_DURATION,
+                RETRIES,
+                BYTES_RECEIVED,
+                THROUGHPUT
+            ]
+        );
+        let operation = ("operation", "storage.objects.get".to_string());
+        assert_eq!(recorded[0].1, 1.0);
+        assert_eq!(
+            recorded[0].2,
+            [operation.clone(), ("status", "200".to_string())]
+        );
+        assert_eq!(recorded[2].1, 1.0);
+        assert_eq!(recorded[3].1, 5.0);
+        assert_eq!(
+            recorded[4].2,
+            [operation, ("direction", "download".to_string())]
+        );
+        Ok(())
+    }
+}
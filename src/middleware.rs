@@ -0,0 +1,285 @@
+//! Hooks that every request of a [`Client`](crate::Client) passes through.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+/// A hook that every request sent by a [`Client`](crate::Client) passes through, which can add
+/// headers to it, log it, time it, or answer it without sending it at all. Middleware is added
+/// through `ClientBuilder::middleware` and is called for every attempt of a request, so that
+/// retried requests pass through it again. The authorization and default headers have already been
+/// added to the request when it reaches the middleware.
+///
+/// Middleware passes the request on by calling `next.run(request)`. The middleware that was added
+/// first is called first, and the last one passes the request on to the HTTP client. Returning a
+/// response or an error without calling `next.run` skips the middleware that comes after and the
+/// HTTP client, which is useful to inject faults in tests. A `reqwest::Response` can be made from
+/// an `http::Response`. Non 2xx responses are turned into an `Error::Http` after the middleware
+/// has run.
+///
+/// ### Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use cloud_storage::{middleware::Next, Client, Middleware};
+///
+/// struct Log;
+///
+/// #[async_trait::async_trait]
+/// impl Middleware for Log {
+///     async fn handle(
+///         &self,
+///         request: reqwest::Request,
+///         next: Next<'_>,
+///     ) -> cloud_storage::Result<reqwest::Response> {
+///         let method = request.method().clone();
+///         let url = request.url().clone();
+///         let start = std::time::Instant::now();
+///         let result = next.run(request).await;
+///         let status = result.as_ref().map(|r| r.status().as_u16()).ok();
+///         println!("{} {} {:?} in {:?}", method, url, status, start.elapsed());
+///         result
+///     }
+/// }
+///
+/// let client = Client::builder()
+///     .default_header("x-goog-user-project".parse()?, "my-project".parse()?)
+///     .middleware(Log)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Handles `request`, usually by passing it on to `next`.
+    async fn handle(
+        &self,
+        request: reqwest::Request,
+        next: Next<'_>,
+    ) -> crate::Result<reqwest::Response>;
+}
+
+/// The middleware that comes after the current one, followed by the HTTP client.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a reqwest::Client,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl std::fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Next")
+            .field("client", &self.client)
+            .field("middleware", &self.middleware.len())
+            .finish()
+    }
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(client: &'a reqwest::Client, middleware: &'a [Arc<dyn Middleware>]) -> Self {
+        Self { client, middleware }
+    }
+
+    /// Passes `request` on to the next middleware, or sends it when there is none left.
+    pub async fn run(self, request: reqwest::Request) -> crate::Result<reqwest::Response> {
+        match self.middleware.split_first() {
+            Some((middleware, rest)) => {
+                middleware
+                    .handle(request, Next::new(self.client, rest))
+                    .await
+            }
+            None => Ok(self.client.execute(request).await?),
+        }
+    }
+}
+
+/// A middleware for tests that answers every request itself, and records the requests it answered.
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use super::{Middleware, Next};
+
+    type Respond = dyn Fn(&Recorded) -> http::Response<Vec<u8>> + Send + Sync;
+
+    /// A request that was answered by a [`Canned`] middleware.
+    #[derive(Debug, Clone)]
+    pub(crate) struct Recorded {
+        pub(crate) method: reqwest::Method,
+        pub(crate) url: reqwest::Url,
+        pub(crate) headers: reqwest::header::HeaderMap,
+        pub(crate) body: Vec<u8>,
+    }
+
+    impl Recorded {
+        /// The value of the `name` header, if it is set.
+        pub(crate) fn header(&self, name: &str) -> Option<&str> {
+            self.headers.get(name).and_then(|h| h.to_str().ok())
+        }
+    }
+
+    /// Answers every request with the response `respond` returns for it.
+    pub(crate) struct Canned {
+        respond: Box<Respond>,
+        requests: Mutex<Vec<Recorded>>,
+    }
+
+    impl Canned {
+        pub(crate) fn new(
+            respond: impl Fn(&Recorded) -> http::Response<Vec<u8>> + Send + Sync + 'static,
+        ) -> Arc<Self> {
+            Arc::new(Self {
+                respond: Box::new(respond),
+                requests: Mutex::new(Vec::new()),
+            })
+        }
+
+        /// Answers the requests with `responses`, in order, and panics when they run out.
+        pub(crate) fn responses(responses: Vec<http::Response<Vec<u8>>>) -> Arc<Self> {
+            let responses = Mutex::new(VecDeque::from(responses));
+            Self::new(move |request| {
+                let next = responses.lock().unwrap().pop_front();
+                next.unwrap_or_else(|| panic!("unexpected request {:?}", request))
+            })
+        }
+
+        /// The requests that were answered so far.
+        pub(crate) fn requests(&self) -> Vec<Recorded> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    /// A response with `status`, `headers` and `body`.
+    pub(crate) fn response(
+        status: u16,
+        headers: &[(&str, &str)],
+        body: impl Into<Vec<u8>>,
+    ) -> http::Response<Vec<u8>> {
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(body.into()).unwrap()
+    }
+
+    /// A token that never expires, so that requests are authenticated without fetching one.
+    pub(crate) struct StaticToken(pub(crate) &'static str);
+
+    #[async_trait::async_trait]
+    impl crate::TokenCache for StaticToken {
+        async fn token_and_exp(&self) -> Option<(String, u64)> {
+            Some((self.0.to_string(), u64::MAX))
+        }
+
+        async fn set_token(&self, _token: String, _exp: u64) -> crate::Result<()> {
+            Ok(())
+        }
+
+        async fn scope(&self) -> String {
+            String::new()
+        }
+
+        async fn fetch_token(&self, _client: &reqwest::Client) -> crate::Result<(String, u64)> {
+            Ok((self.0.to_string(), u64::MAX))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Middleware for Arc<Canned> {
+        async fn handle(
+            &self,
+            request: reqwest::Request,
+            _next: Next<'_>,
+        ) -> crate::Result<reqwest::Response> {
+            let body = request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .unwrap_or_default();
+            let recorded = Recorded {
+                method: request.method().clone(),
+                url: request.url().clone(),
+                headers: request.headers().clone(),
+                body: body.to_vec(),
+            };
+            let response = (self.respond)(&recorded);
+            self.requests.lock().unwrap().push(recorded);
+            Ok(response.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    struct Tag;
+
+    #[async_trait]
+    impl Middleware for Tag {
+        async fn handle(
+            &self,
+            mut request: reqwest::Request,
+            next: Next<'_>,
+        ) -> crate::Result<reqwest::Response> {
+            request
+                .headers_mut()
+                .insert("x-audit-tag", "unit-test".parse()?);
+            next.run(request).await
+        }
+    }
+
+    // Answers every request itself, failing the first attempt with a 503.
+    #[derive(Default)]
+    struct Faults {
+        attempts: AtomicUsize,
+        tags: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Arc<Faults> {
+        async fn handle(
+            &self,
+            request: reqwest::Request,
+            _next: Next<'_>,
+        ) -> crate::Result<reqwest::Response> {
+            let tag = request.headers().get("x-audit-tag");
+            let tag = tag.and_then(|t| t.to_str().ok()).map(String::from);
+            self.tags.lock().unwrap().push(tag);
+            let status = match self.attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => 503,
+                _ => 200,
+            };
+            let response = http::Response::builder()
+                .status(status)
+                .body("hello")
+                .unwrap();
+            Ok(response.into())
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware() -> Result<(), Box<dyn std::error::Error>> {
+        let faults = Arc::new(Faults::default());
+        let client = crate::Client::builder()
+            .anonymous()
+            .endpoint("http://storage.invalid")
+            .retry_policy(
+                crate::RetryPolicy::new().initial_backoff(std::time::Duration::from_millis(1)),
+            )
+            .middleware(Tag)
+            .middleware(faults.clone())
+            .build()?;
+        let data = client.object().download("bucket", "object").await?;
+        assert_eq!(data, b"hello");
+        assert_eq!(faults.attempts.load(Ordering::SeqCst), 2);
+        let tags = faults.tags.lock().unwrap().clone();
+        assert_eq!(tags, vec![Some("unit-test".to_string()); 2]);
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+//! [V4 POST policy documents](https://cloud.google.com/storage/docs/xml-api/post-object-forms),
+//! which let browsers upload objects directly through an HTML form.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::{
+    signed_url::{check_duration, credential_scope, timestamp},
+    signer::Signer,
+    SignedUrlBuilder, UrlStyle,
+};
+
+/// Builds a signed policy document that allows an HTML form to upload an object, as long as the
+/// upload satisfies the conditions of the policy.
+///
+/// Fields that are added through this builder, such as `content_type` or
+/// `success_action_redirect`, become both a condition of the policy and a field of the form.
+/// Conditions that only restrict what the form may contain, such as `content_length_range` or
+/// `starts_with`, do not add a field.
+///
+/// ### Example
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use cloud_storage::{post_policy::PostPolicyBuilder, Client};
+///
+/// let client = Client::default();
+/// let builder = PostPolicyBuilder::with_key_prefix("my_bucket", "uploads/")
+///     .duration(600)
+///     .content_length_range(0, 10 * 1024 * 1024)
+///     .starts_with("Content-Type", "image/")
+///     .success_action_redirect("https://example.com/done");
+/// let policy = client.object().sign_post_policy(&builder).await?;
+/// // render a form that posts to `policy.url`, with a hidden input for each of `policy.fields`,
+/// // followed by a file input named `file`
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PostPolicyBuilder {
+    bucket: String,
+    key: Key,
+    duration: u32,
+    issued_at: Option<DateTime<Utc>>,
+    conditions: Vec<Value>,
+    fields: BTreeMap<String, String>,
+    /// Determines the url of the form, which is that of the bucket
+    url: SignedUrlBuilder,
+}
+
+#[derive(Debug, Clone)]
+enum Key {
+    Exact(String),
+    Prefix(String),
+}
+
+/// A signed policy document, together with the url to which the form must be posted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostPolicy {
+    /// The url to which the form is posted, such as `https://storage.googleapis.com/my_bucket/`.
+    pub url: String,
+    /// The fields that the form must include, by name, including the `policy` and the
+    /// `x-goog-signature`. The file itself must be the last field of the form, named `file`.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl PostPolicyBuilder {
+    /// Creates a builder for a policy that allows uploading the object `key` to `bucket`, and
+    /// that is valid for an hour from the moment it is signed.
+    pub fn new(bucket: impl Into<String>, key: impl Into<String>) -> Self {
+        Self::with_key(bucket.into(), Key::Exact(key.into()))
+    }
+
+    /// Creates a builder for a policy that allows uploading any object whose name starts with
+    /// `prefix`. The `key` field of the policy is set to `prefix`, and should be completed by the
+    /// form before it is posted.
+    pub fn with_key_prefix(bucket: impl Into<String>, prefix: impl Into<String>) -> Self {
+        Self::with_key(bucket.into(), Key::Prefix(prefix.into()))
+    }
+
+    fn with_key(bucket: String, key: Key) -> Self {
+        Self {
+            url: SignedUrlBuilder::new(bucket.clone(), ""),
+            bucket,
+            key,
+            duration: 3600,
+            issued_at: None,
+            conditions: Vec::new(),
+            fields: BTreeMap::new(),
+        }
+    }
+
+    /// Sets how the bucket appears in the url of the form, as for
+    /// [`SignedUrlBuilder::url_style`].
+    pub fn url_style(mut self, url_style: UrlStyle) -> Self {
+        self.url = self.url.url_style(url_style);
+        self
+    }
+
+    /// Sets the scheme of the url of the form, which defaults to `https`.
+    pub fn scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.url = self.url.scheme(scheme);
+        self
+    }
+
+    /// Sets the host of the url of the form, which defaults to `storage.googleapis.com`.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.url = self.url.host(host);
+        self
+    }
+
+    /// Sets the number of seconds for which the policy is valid, which may not exceed
+    /// [`MAX_DURATION`](crate::signed_url::MAX_DURATION).
+    pub fn duration(mut self, seconds: u32) -> Self {
+        self.duration = seconds;
+        self
+    }
+
+    /// Sets the moment from which the policy is valid, instead of the moment at which it is
+    /// signed.
+    pub fn issued_at(mut self, issued_at: DateTime<Utc>) -> Self {
+        self.issued_at = Some(issued_at);
+        self
+    }
+
+    /// Requires the size of the uploaded file to be between `min` and `max` bytes, inclusive.
+    pub fn content_length_range(mut self, min: u64, max: u64) -> Self {
+        self.conditions
+            .push(json!(["content-length-range", min, max]));
+        self
+    }
+
+    /// Requires the form field `name`, such as `Content-Type` or `x-goog-meta-owner`, to start
+    /// with `prefix`. An empty prefix allows any value.
+    pub fn starts_with(mut self, name: &str, prefix: impl Into<String>) -> Self {
+        let name = format!("${}", name);
+        self.conditions
+            .push(json!(["starts-with", name, prefix.into()]));
+        self
+    }
+
+    /// Adds the form field `name` with `value`, and requires the form to contain exactly that
+    /// value. Use this for fields such as `acl`, `Cache-Control` or `x-goog-meta-` fields.
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let (name, value) = (name.into(), value.into());
+        self.conditions.push(json!({ name.clone(): value.clone() }));
+        self.fields.insert(name, value);
+        self
+    }
+
+    /// Sets the content type of the uploaded object.
+    pub fn content_type(self, content_type: impl Into<String>) -> Self {
+        self.field("Content-Type", content_type)
+    }
+
+    /// Redirects the browser to `url` after a successful upload.
+    pub fn success_action_redirect(self, url: impl Into<String>) -> Self {
+        self.field("success_action_redirect", url)
+    }
+
+    /// Sets the status code of the response to a successful upload, which is `204` by default.
+    /// Google accepts `200`, `201` and `204`.
+    pub fn success_action_status(self, status: u16) -> Self {
+        self.field("success_action_status", status.to_string())
+    }
+
+    /// Signs the policy with `signer`.
+    pub async fn sign(&self, signer: &dyn Signer) -> crate::Result<PostPolicy> {
+        check_duration(self.duration)?;
+        let issued_at = self.issued_at.unwrap_or_else(Utc::now);
+        let expiration = issued_at + chrono::Duration::seconds(self.duration.into());
+        let scope = credential_scope(&issued_at);
+
+        let mut fields = self.fields.clone();
+        let (key, key_condition) = match &self.key {
+            Key::Exact(key) => (key, json!({ "key": key })),
+            Key::Prefix(prefix) => (prefix, json!(["starts-with", "$key", prefix])),
+        };
+        fields.insert("key".to_string(), key.clone());
+        fields.insert("x-goog-date".to_string(), timestamp(&issued_at));
+        fields.insert(
+            "x-goog-credential".to_string(),
+            format!("{}/{}", signer.client_email(), scope),
+        );
+        fields.insert(
+            "x-goog-algorithm".to_string(),
+            signer.algorithm().as_str().to_string(),
+        );
+
+        let mut conditions = self.conditions.clone();
+        conditions.push(json!({ "bucket": self.bucket }));
+        conditions.push(key_condition);
+        for name in &["x-goog-date", "x-goog-credential", "x-goog-algorithm"] {
+            conditions.push(json!({ *name: fields[*name] }));
+        }
+        let policy = json!({
+            "conditions": conditions,
+            "expiration": expiration.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        });
+        // the base64 encoded policy is both a field of the form and the string that is signed
+        let policy = base64::encode(escape_non_ascii(&serde_json::to_string(&policy)?));
+        let signature = signer.sign(&scope, policy.as_bytes()).await?;
+        fields.insert("policy".to_string(), policy);
+        fields.insert("x-goog-signature".to_string(), hex::encode(signature));
+
+        Ok(PostPolicy {
+            url: self.url.bucket_url(),
+            fields,
+        })
+    }
+}
+
+/// Replaces the characters of `json` that are not ASCII by `\uXXXX` escapes, as Google expects
+/// them in a policy document. Characters outside of the Basic Multilingual Plane become a
+/// surrogate pair.
+fn escape_non_ascii(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::service_account::ServiceAccount;
+
+    #[tokio::test]
+    async fn post_policy() -> Result<(), Box<dyn std::error::Error>> {
+        let service_account = ServiceAccount::test();
+        let signer = crate::ServiceAccountSigner::new(service_account.clone());
+        let policy = PostPolicyBuilder::with_key_prefix("test-bucket", "uploads/")
+            .duration(10)
+            .issued_at("2020-01-23T04:35:30Z".parse()?)
+            .content_length_range(246, 266)
+            .starts_with("Content-Type", "image/")
+            .success_action_redirect("https://example.com/done")
+            .field("x-goog-meta-owner", "Alice")
+            .sign(&signer)
+            .await?;
+        assert_eq!(policy.url, "https://storage.googleapis.com/test-bucket/");
+
+        let credential = format!(
+            "{}/20200123/auto/storage/goog4_request",
+            service_account.client_email
+        );
+        let fields = &policy.fields;
+        assert_eq!(fields["key"], "uploads/");
+        assert_eq!(fields["x-goog-date"], "20200123T043530Z");
+        assert_eq!(fields["x-goog-credential"], credential);
+        assert_eq!(fields["x-goog-algorithm"], "GOOG4-RSA-SHA256");
+        assert_eq!(
+            fields["success_action_redirect"],
+            "https://example.com/done"
+        );
+        assert_eq!(fields["x-goog-meta-owner"], "Alice");
+        assert!(!fields.contains_key("Content-Type"));
+
+        let document: Value = serde_json::from_slice(&base64::decode(&fields["policy"])?)?;
+        let expected = json!({
+            "conditions": [
+                ["content-length-range", 246, 266],
+                ["starts-with", "$Content-Type", "image/"],
+                { "success_action_redirect": "https://example.com/done" },
+                { "x-goog-meta-owner": "Alice" },
+                { "bucket": "test-bucket" },
+                ["starts-with", "$key", "uploads/"],
+                { "x-goog-date": "20200123T043530Z" },
+                { "x-goog-credential": credential },
+                { "x-goog-algorithm": "GOOG4-RSA-SHA256" },
+            ],
+            "expiration": "2020-01-23T04:35:40Z",
+        });
+        assert_eq!(document, expected);
+
+        // computed outside of this crate with the key of the test service account
+        assert_eq!(
+            fields["x-goog-signature"],
+            "0b8a13ca0673f7e23a563dae1f916f89c89851c46fb1037111252bd27e6a7244e6ea19a6508b87199e2b84ef1e856d6b1aa77e382e552b11a0367b444109458275aef703ce59b38f924c80d0f5e4c0474df2dc90e65098f1985b1d67ce279b52bfd4ac570863b7afb4411b8367f9bd8c6775580b23c97bcdd0c1dbb02aee4599424c1c1d9e4bce9c9643d9f4f8e1e44f34673ce54596e7879a9b1c62abc46ba2273d4f61c5c6a8e67a0113d91f7c89c4767daa467ded797ab4f67677b67cc4ebde87b6b525292cc39cb87cce73afac29e372cd138ac0999d4a22aeae41c2275b8c418eab0543e43ebb03417fcf4890ae950418cad341216d29c9fc6d266596d4"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_non_ascii() {
+        assert_eq!(
+            escape_non_ascii(r#"{"key":"ó \"𝄞\"\n"}"#),
+            r#"{"key":"\u00f3 \"\ud834\udd1e\"\n"}"#
+        );
+    }
+
+    /// The input of a case of Google's
+    /// [conformance tests](https://github.com/googleapis/conformance-tests/blob/main/storage/v1/v4_signatures.json).
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PolicyInput {
+        scheme: String,
+        bucket: String,
+        object: String,
+        expiration: u32,
+        timestamp: DateTime<Utc>,
+        #[serde(default)]
+        conditions: PolicyConditions,
+        #[serde(default)]
+        fields: BTreeMap<String, String>,
+        url_style: Option<String>,
+        bucket_bound_hostname: Option<String>,
+        client_endpoint: Option<String>,
+        universe_domain: Option<String>,
+    }
+
+    impl PolicyInput {
+        /// The feature of the case that this crate does not support, if any.
+        fn unsupported(&self) -> Option<&'static str> {
+            if self.client_endpoint.is_some() {
+                Some("custom client endpoints")
+            } else if self.universe_domain.is_some() {
+                Some("universe domains")
+            } else {
+                None
+            }
+        }
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PolicyConditions {
+        starts_with: Option<(String, String)>,
+        content_length_range: Option<(u64, u64)>,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PolicyOutput {
+        url: String,
+        fields: BTreeMap<String, String>,
+        expected_decoded_policy: String,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PostPolicyV4Test {
+        description: String,
+        policy_input: PolicyInput,
+        policy_output: PolicyOutput,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ConformanceTests {
+        post_policy_v4_tests: Vec<PostPolicyV4Test>,
+    }
+
+    /// Checks the url, the fields and the policy document of every supported case of `tests`,
+    /// and returns how many cases were checked.
+    async fn check_post_policy_v4_tests(
+        tests: &str,
+        service_account: ServiceAccount,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let tests: ConformanceTests = serde_json::from_str(tests)?;
+        let signer = crate::ServiceAccountSigner::new(service_account);
+        let mut checked = 0;
+        for test in tests.post_policy_v4_tests {
+            let input = test.policy_input;
+            if let Some(feature) = input.unsupported() {
+                println!("skipping {:?}, which uses {}", test.description, feature);
+                continue;
+            }
+            let mut builder = match &input.conditions.starts_with {
+                Some((name, prefix)) if name == "$key" => {
+                    PostPolicyBuilder::with_key_prefix(&input.bucket, prefix)
+                }
+                _ => PostPolicyBuilder::new(&input.bucket, &input.object),
+            }
+            .duration(input.expiration)
+            .issued_at(input.timestamp)
+            .scheme(input.scheme);
+            if let Some((min, max)) = input.conditions.content_length_range {
+                builder = builder.content_length_range(min, max);
+            }
+            if let Some((name, prefix)) = &input.conditions.starts_with {
+                if name != "$key" {
+                    builder = builder.starts_with(name.trim_start_matches('$'), prefix);
+                }
+            }
+            // the conformance tests mark fields that are not part of the policy this way
+            for (name, value) in input.fields {
+                if !name.starts_with("x-ignore-") {
+                    builder = builder.field(name, value);
+                }
+            }
+            builder = match (input.url_style.as_deref(), input.bucket_bound_hostname) {
+                (Some("VIRTUAL_HOSTED_STYLE"), _) => {
+                    builder.url_style(UrlStyle::VirtualHostedStyle)
+                }
+                (Some("BUCKET_BOUND_HOSTNAME"), Some(host)) => {
+                    builder.url_style(UrlStyle::BucketBoundHostname(host))
+                }
+                (None, _) | (Some("PATH_STYLE"), _) => builder,
+                (style, _) => panic!("{}: unexpected url style {:?}", test.description, style),
+            };
+
+            let policy = builder.sign(&signer).await?;
+            let expected = test.policy_output;
+            assert_eq!(policy.url, expected.url, "{}", test.description);
+            let decoded = String::from_utf8(base64::decode(&policy.fields["policy"])?)?;
+            assert_eq!(
+                decoded, expected.expected_decoded_policy,
+                "{}",
+                test.description
+            );
+            assert_eq!(policy.fields, expected.fields, "{}", test.description);
+            checked += 1;
+        }
+        Ok(checked)
+    }
+
+    // The cases of Google's conformance tests, signed with the key that is published with them.
+    #[tokio::test]
+    #[ignore = "needs the files that test-data/conformance/fetch.sh downloads"]
+    async fn conformance() -> Result<(), Box<dyn std::error::Error>> {
+        let tests = crate::read_conformance_file("v4_signatures.json");
+        assert!(check_post_policy_v4_tests(&tests, ServiceAccount::conformance()).await? > 0);
+        Ok(())
+    }
+
+    // Further cases in the same format, which were computed outside of this crate with the key of
+    // the test service account.
+    #[tokio::test]
+    async fn vectors() -> Result<(), Box<dyn std::error::Error>> {
+        let tests = include_str!("../test-data/v4_vectors.json");
+        assert_eq!(
+            check_post_policy_v4_tests(tests, ServiceAccount::test()).await?,
+            8
+        );
+        Ok(())
+    }
+}
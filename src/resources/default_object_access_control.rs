@@ -6,7 +6,7 @@ use crate::{error::GoogleResponse, resources::common::ListResponse};
 /// The DefaultObjectAccessControls resources represent the Access Control Lists (ACLs) applied to a
 /// new object within Google Cloud Storage when no ACL was provided for that object. ACLs let you
 /// specify who has access to your data and to what extent.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DefaultObjectAccessControl {
     /// The kind of item this is. For object access control entries, this is always
@@ -28,7 +28,7 @@ pub struct DefaultObjectAccessControl {
     /// * The user liz@example.com would be user-liz@example.com.
     /// * The group example@googlegroups.com would be group-example@googlegroups.com.
     /// * To refer to all members of the G Suite for Business domain example.com, the entity would
-    /// be domain-example.com.
+    ///   be domain-example.com.
     pub entity: Entity,
     /// The access permission for the entity.
     pub role: Role,
@@ -49,7 +49,7 @@ pub struct DefaultObjectAccessControl {
 }
 
 /// Model that can be used to create a new DefaultObjectAccessControl object.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewDefaultObjectAccessControl {
     /// The entity holding the permission, in one of the following forms:
@@ -68,7 +68,7 @@ pub struct NewDefaultObjectAccessControl {
     /// * The user liz@example.com would be user-liz@example.com.
     /// * The group example@googlegroups.com would be group-example@googlegroups.com.
     /// * To refer to all members of the G Suite for Business domain example.com, the entity would
-    /// be domain-example.com.
+    ///   be domain-example.com.
     pub entity: Entity,
     /// The access permission for the entity.
     pub role: Role,
@@ -274,10 +274,11 @@ mod tests {
     #[tokio::test]
     async fn read() -> Result<(), Box<dyn std::error::Error>> {
         let bucket = crate::read_test_bucket().await;
-        NewDefaultObjectAccessControl {
+        let new_acl = NewDefaultObjectAccessControl {
             entity: Entity::AllUsers,
             role: Role::Reader,
         };
+        DefaultObjectAccessControl::create(&bucket.name, &new_acl).await?;
         DefaultObjectAccessControl::read(&bucket.name, &Entity::AllUsers).await?;
         Ok(())
     }
@@ -305,8 +306,11 @@ mod tests {
     #[tokio::test]
     async fn delete() -> Result<(), Box<dyn std::error::Error>> {
         let bucket = crate::read_test_bucket().await;
-        let default_acl =
-            DefaultObjectAccessControl::read(&bucket.name, &Entity::AllAuthenticatedUsers).await?;
+        let new_acl = NewDefaultObjectAccessControl {
+            entity: Entity::AllUsers,
+            role: Role::Reader,
+        };
+        let default_acl = DefaultObjectAccessControl::create(&bucket.name, &new_acl).await?;
         default_acl.delete().await?;
         Ok(())
     }
@@ -1,5 +1,5 @@
 /// Deeply nested enum that represents a location where a bucket might store its files.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
 pub enum Location {
     /// Objects are stored in a single location.
@@ -8,6 +8,9 @@ pub enum Location {
     Multi(MultiRegion),
     /// Objects are stored redundantly accross two locations.
     Dual(DualRegion),
+    /// A location that is not known to this crate, such as a region or dual-region that was
+    /// introduced after it was released, or a configurable dual-region.
+    Unknown(String),
 }
 
 impl Default for Location {
@@ -17,7 +20,7 @@ impl Default for Location {
 }
 
 /// The possible options for single regions.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
 pub enum SingleRegion {
     /// All options in North America.
@@ -34,7 +37,7 @@ pub enum SingleRegion {
 
 /// All options in North America.
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum NALocation {
     /// Store the files in Montréal.
     #[serde(rename = "NORTHAMERICA-NORTHEAST1")]
@@ -58,7 +61,7 @@ pub enum NALocation {
 
 /// All options in South America.
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum SALocation {
     /// Store the files in Soa Paulo.
     #[serde(rename = "SOUTHAMERICA-EAST1")]
@@ -66,7 +69,7 @@ pub enum SALocation {
 }
 
 /// All options in Europe.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum EuropeLocation {
     /// Store the files in Finland.
     #[serde(rename = "EUROPE-NORTH1")]
@@ -89,7 +92,7 @@ pub enum EuropeLocation {
 }
 
 /// ALl options in Asia.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum AsiaLocation {
     /// Store the files in Taiwan.
     #[serde(rename = "ASIA-EAST1")]
@@ -112,7 +115,7 @@ pub enum AsiaLocation {
 }
 
 /// All options in Australia.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum AusLocation {
     /// Store the files in Sydney.
     #[serde(rename = "AUSTRALIA-SOUTHEAST1")]
@@ -120,7 +123,7 @@ pub enum AusLocation {
 }
 
 /// The possible options for multi-region storage.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum MultiRegion {
     /// Data centers in Asia
@@ -135,7 +138,7 @@ pub enum MultiRegion {
 }
 
 /// The possible options for dual-region storage
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum DualRegion {
     /// EUROPE-NORTH1 and EUROPE-WEST4. Additionally, object metadata may be stored in EUROPE-WEST1.
@@ -143,3 +146,19 @@ pub enum DualRegion {
     /// US-CENTRAL1 and US-EAST1. Additionally, object metadata may be stored in Tulsa, Oklahoma.
     Nam4,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_location() -> Result<(), Box<dyn std::error::Error>> {
+        let location: Location = serde_json::from_str(r#""EUR4""#)?;
+        assert_eq!(location, Location::Dual(DualRegion::Eur4));
+
+        let location: Location = serde_json::from_str(r#""MARS-NORTH1""#)?;
+        assert_eq!(location, Location::Unknown("MARS-NORTH1".to_string()));
+        assert_eq!(serde_json::to_string(&location)?, r#""MARS-NORTH1""#);
+        Ok(())
+    }
+}
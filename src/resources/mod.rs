@@ -22,7 +22,9 @@ pub mod object_access_control;
 /// A deserialized version of the `service-account-********.json` file. Used to authenticate
 /// requests.
 pub mod service_account;
-/// Used for parsing the `service-account-********.json` file.
+/// The response of the IAM Credentials `signBlob` endpoint.
+#[allow(dead_code)]
 pub(crate) mod signature;
 /// The topic field of a `Notification`
+#[allow(dead_code)]
 mod topic;
@@ -1,5 +1,5 @@
 pub use crate::resources::bucket::Owner;
-use crate::resources::object_access_control::ObjectAccessControl;
+use crate::{resources::object_access_control::ObjectAccessControl, signed_url::SignedUrlBuilder};
 use futures_util::Stream;
 #[cfg(feature = "global-client")]
 use futures_util::TryStream;
@@ -7,7 +7,7 @@ use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use std::collections::HashMap;
 
 /// A resource representing a file in Google Cloud Storage.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Object {
     /// The kind of item this is. For objects, this is always `storage#object`.
@@ -96,7 +96,7 @@ pub struct Object {
 }
 
 /// Contains data about how a user might encrypt their files in Google Cloud Storage.
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CustomerEncrypton {
     /// The encryption algorithm.
@@ -106,7 +106,7 @@ pub struct CustomerEncrypton {
 }
 
 /// The request that is supplied to perform `Object::compose`.
-#[derive(Debug, PartialEq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ComposeRequest {
     /// The kind of item this is. Will always be `storage#composeRequest`.
@@ -118,7 +118,7 @@ pub struct ComposeRequest {
 }
 
 /// A SourceObject represents one of the objects that is to be composed.
-#[derive(Debug, PartialEq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SourceObject {
     /// The source object's name. All source objects must have the same storage class and reside in
@@ -131,7 +131,7 @@ pub struct SourceObject {
 }
 
 /// Allows conditional copying of this file.
-#[derive(Debug, PartialEq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectPrecondition {
     /// Only perform the composition if the generation of the source object that would be used
@@ -140,6 +140,28 @@ pub struct ObjectPrecondition {
     pub if_generation_match: i64,
 }
 
+/// Conditions on the generation and metageneration of the targeted object that must hold for a
+/// request to succeed. If one of them does not hold, the request fails with status
+/// `412 Precondition Failed`. See [the Google Cloud Storage
+/// documentation](https://cloud.google.com/storage/docs/request-preconditions) for more details.
+#[derive(Debug, PartialEq, serde::Serialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Preconditions {
+    /// Only perform the request if the generation of the object matches this value. The value
+    /// `0` requires that the object does not exist yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_generation_match: Option<i64>,
+    /// Only perform the request if the generation of the object does not match this value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_generation_not_match: Option<i64>,
+    /// Only perform the request if the metageneration of the object matches this value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_metageneration_match: Option<i64>,
+    /// Only perform the request if the metageneration of the object does not match this value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_metageneration_not_match: Option<i64>,
+}
+
 /// The request that is supplied to perform `Object::list`.
 /// See [the Google Cloud Storage API
 /// reference](https://cloud.google.com/storage/docs/json_api/v1/objects/list)
@@ -328,8 +350,7 @@ impl Object {
         mime_type: &str,
     ) -> crate::Result<Self> {
         let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)
-            .map_err(|e| crate::Error::Other(e.to_string()))?;
+        file.read_to_end(&mut buffer)?;
 
         let stream = futures_util::stream::once(async { Ok::<_, crate::Error>(buffer) });
 
@@ -603,6 +624,7 @@ impl Object {
     /// * Geographical Location,
     /// * Encryption,
     /// * Storage class.
+    ///
     /// These limitations mean that for now, the rewrite and the copy methods do the same thing.
     /// ### Example
     /// ```no_run
@@ -651,7 +673,7 @@ impl Object {
     /// # }
     /// ```
     pub fn download_url(&self, duration: u32) -> crate::Result<String> {
-        self.sign(&self.name, duration, "GET", None, &HashMap::new())
+        self.sign(duration, "GET", None, &HashMap::new())
     }
 
     /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
@@ -676,13 +698,7 @@ impl Object {
         duration: u32,
         opts: crate::DownloadOptions,
     ) -> crate::Result<String> {
-        self.sign(
-            &self.name,
-            duration,
-            "GET",
-            opts.content_disposition,
-            &HashMap::new(),
-        )
+        self.sign(duration, "GET", opts.content_disposition, &HashMap::new())
     }
 
     /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
@@ -703,7 +719,7 @@ impl Object {
     /// # }
     /// ```
     pub fn upload_url(&self, duration: u32) -> crate::Result<String> {
-        self.sign(&self.name, duration, "PUT", None, &HashMap::new())
+        self.sign(duration, "PUT", None, &HashMap::new())
     }
 
     /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
@@ -731,10 +747,10 @@ impl Object {
         duration: u32,
         custom_metadata: HashMap<String, String>,
     ) -> crate::Result<(String, HashMap<String, String>)> {
-        let url = self.sign(&self.name, duration, "PUT", None, &custom_metadata)?;
+        let url = self.sign(duration, "PUT", None, &custom_metadata)?;
         let mut headers = HashMap::new();
         for (k, v) in custom_metadata.iter() {
-            headers.insert(format!("x-goog-meta-{}", k.to_string()), v.to_string());
+            headers.insert(format!("x-goog-meta-{}", k), v.to_string());
         }
         Ok((url, headers))
     }
@@ -743,226 +759,48 @@ impl Object {
     // /// which is valid for `duration` seconds, and lets the posessor upload new file contents.
     // /// without any authentication.
     // pub fn upload_url(&self, duration: u32) -> crate::Result<String> {
-    //     self.sign(&self.name, duration, "POST")
+    //     self.sign(duration, "POST")
     // }
 
-    #[inline(always)]
-    fn sign(
+    /// Returns a builder for a signed url that performs `http_verb` on this object.
+    pub(crate) fn signed_url(
         &self,
-        file_path: &str,
         duration: u32,
         http_verb: &str,
         content_disposition: Option<String>,
         custom_metadata: &HashMap<String, String>,
-    ) -> crate::Result<String> {
-        if duration > 604800 {
-            let msg = format!(
-                "duration may not be greater than 604800, but was {}",
-                duration
-            );
-            return Err(crate::Error::Other(msg));
-        }
-
-        // 0 Sort and construct the canonical headers
-        let mut headers = vec![("host".to_string(), "storage.googleapis.com".to_string())];
-        // Add custom metadata headers, guaranteed unique by HashMap input
+    ) -> SignedUrlBuilder {
+        let mut builder = SignedUrlBuilder::new(&self.bucket, &self.name)
+            .method(http_verb)
+            .duration(duration);
         for (k, v) in custom_metadata.iter() {
-            headers.push((format!("x-goog-meta-{}", k.to_string()), v.to_string()));
+            builder = builder.header(format!("x-goog-meta-{}", k), v);
         }
-        headers.sort_unstable_by(|(k1, _), (k2, _)| k1.cmp(k2));
-        let canonical_headers: String = headers
-            .iter()
-            .map(|(k, v)| format!("{}:{}", k.to_lowercase(), v.to_lowercase()))
-            .collect::<Vec<String>>()
-            .join("\n");
-        let signed_headers = headers
-            .iter()
-            .map(|(k, _)| k.to_lowercase())
-            .collect::<Vec<String>>()
-            .join(";");
-
-        // 1 construct the canonical request
-        let issue_date = chrono::Utc::now();
-        let file_path = self.path_to_resource(file_path);
-        let query_string = Self::get_canonical_query_string(
-            &issue_date,
-            duration,
-            &signed_headers,
-            content_disposition,
-        );
-        let canonical_request = self.get_canonical_request(
-            &file_path,
-            &query_string,
-            http_verb,
-            &canonical_headers,
-            &signed_headers,
-        );
-
-        // 2 get hex encoded SHA256 hash the canonical request
-        let hex_hash = hex::encode(crypto::sha256(canonical_request.as_bytes()).as_ref());
-
-        // 3 construct the string to sign
-        let string_to_sign = format!(
-            "{signing_algorithm}\n\
-            {current_datetime}\n\
-            {credential_scope}\n\
-            {hashed_canonical_request}",
-            signing_algorithm = "GOOG4-RSA-SHA256",
-            current_datetime = issue_date.format("%Y%m%dT%H%M%SZ"),
-            credential_scope = Self::get_credential_scope(&issue_date),
-            hashed_canonical_request = hex_hash,
-        );
-
-        // 4 sign the string to sign with RSA - SHA256
-        let signature = hex::encode(crypto::rsa_pkcs1_sha256(&string_to_sign)?);
-
-        // 5 construct the signed url
-        Ok(format!(
-            "https://storage.googleapis.com{path_to_resource}?\
-            {query_string}&\
-            X-Goog-Signature={request_signature}",
-            path_to_resource = file_path,
-            query_string = query_string,
-            request_signature = signature,
-        ))
+        if let Some(cd) = content_disposition {
+            builder = builder.query_param("response-content-disposition", cd);
+        }
+        builder
     }
 
     #[inline(always)]
-    fn get_canonical_request(
+    fn sign(
         &self,
-        path: &str,
-        query_string: &str,
+        duration: u32,
         http_verb: &str,
-        headers: &str,
-        signed_headers: &str,
-    ) -> String {
-        format!(
-            "{http_verb}\n\
-            {path_to_resource}\n\
-            {canonical_query_string}\n\
-            {canonical_headers}\n\
-            \n\
-            {signed_headers}\n\
-            {payload}",
-            http_verb = http_verb,
-            path_to_resource = path,
-            canonical_query_string = query_string,
-            canonical_headers = headers,
-            signed_headers = signed_headers,
-            payload = "UNSIGNED-PAYLOAD",
-        )
-    }
-
-    #[inline(always)]
-    fn get_canonical_query_string(
-        date: &chrono::DateTime<chrono::Utc>,
-        exp: u32,
-        headers: &str,
         content_disposition: Option<String>,
-    ) -> String {
-        let credential = format!(
-            "{authorizer}/{scope}",
-            authorizer = crate::SERVICE_ACCOUNT.client_email,
-            scope = Self::get_credential_scope(date),
-        );
-        let mut s = format!(
-            "X-Goog-Algorithm={algo}&\
-            X-Goog-Credential={cred}&\
-            X-Goog-Date={date}&\
-            X-Goog-Expires={exp}&\
-            X-Goog-SignedHeaders={signed}",
-            algo = "GOOG4-RSA-SHA256",
-            cred = percent_encode(&credential),
-            date = date.format("%Y%m%dT%H%M%SZ"),
-            exp = exp,
-            signed = percent_encode(headers),
-        );
-        if let Some(cd) = content_disposition {
-            s.push_str(&format!("&response-content-disposition={}", cd));
-        }
-        s
-    }
-
-    #[inline(always)]
-    fn path_to_resource(&self, path: &str) -> String {
-        format!(
-            "/{bucket}/{file_path}",
-            bucket = self.bucket,
-            file_path = percent_encode_noslash(path),
-        )
-    }
-
-    #[inline(always)]
-    fn get_credential_scope(date: &chrono::DateTime<chrono::Utc>) -> String {
-        format!("{}/henk/storage/goog4_request", date.format("%Y%m%d"))
-    }
-}
-
-#[cfg(feature = "openssl")]
-mod openssl {
-    #[inline(always)]
-    pub fn rsa_pkcs1_sha256(message: &str) -> crate::Result<Vec<u8>> {
-        use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
-
-        let key = PKey::private_key_from_pem(crate::SERVICE_ACCOUNT.private_key.as_bytes())?;
-        let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
-        signer.update(message.as_bytes())?;
-        Ok(signer.sign_to_vec()?)
-    }
-
-    #[inline(always)]
-    pub fn sha256(bytes: &[u8]) -> impl AsRef<[u8]> {
-        openssl::sha::sha256(bytes)
-    }
-}
-
-#[cfg(feature = "ring")]
-mod ring {
-    #[cfg_attr(all(feature = "ring", feature = "openssl"), allow(dead_code))]
-    #[inline(always)]
-    pub fn rsa_pkcs1_sha256(message: &str) -> crate::Result<Vec<u8>> {
-        use ring::{
-            rand::SystemRandom,
-            signature::{RsaKeyPair, RSA_PKCS1_SHA256},
-        };
-
-        let key_pem = pem::parse(crate::SERVICE_ACCOUNT.private_key.as_bytes())?;
-        let key = RsaKeyPair::from_pkcs8(&key_pem.contents)?;
-        let rng = SystemRandom::new();
-        let mut signature = vec![0; key.public_modulus_len()];
-        key.sign(&RSA_PKCS1_SHA256, &rng, message.as_bytes(), &mut signature)?;
-        Ok(signature)
-    }
-
-    #[cfg_attr(all(feature = "ring", feature = "openssl"), allow(dead_code))]
-    #[inline(always)]
-    pub fn sha256(bytes: &[u8]) -> impl AsRef<[u8]> {
-        use ring::digest::{digest, SHA256};
-        digest(&SHA256, bytes)
+        custom_metadata: &HashMap<String, String>,
+    ) -> crate::Result<String> {
+        self.signed_url(duration, http_verb, content_disposition, custom_metadata)
+            .sign_with_service_account()
     }
 }
 
-mod crypto {
-    #[cfg(feature = "openssl")]
-    pub use super::openssl::*;
-    #[cfg(all(feature = "ring", not(feature = "openssl")))]
-    pub use super::ring::*;
-}
-
 const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
     .remove(b'*')
     .remove(b'-')
     .remove(b'.')
     .remove(b'_');
 
-const NOSLASH_ENCODE_SET: &AsciiSet = &ENCODE_SET.remove(b'/').remove(b'~');
-
-// We need to be able to percent encode stuff, but without touching the slashes in filenames. To
-// this end we create an implementation that does this, without touching the slashes.
-fn percent_encode_noslash(input: &str) -> String {
-    utf8_percent_encode(input, NOSLASH_ENCODE_SET).to_string()
-}
-
 pub(crate) fn percent_encode(input: &str) -> String {
     utf8_percent_encode(input, ENCODE_SET).to_string()
 }
@@ -973,6 +811,11 @@ mod tests {
     use crate::Error;
     use futures_util::{stream, StreamExt, TryStreamExt};
 
+    // Signs a download url with the signer of the global client, on the host of its endpoint.
+    async fn download_url(object: &Object) -> crate::Result<String> {
+        crate::CLOUD_CLIENT.object().download_url(object, 100).await
+    }
+
     #[tokio::test]
     async fn create() -> Result<(), Box<dyn std::error::Error>> {
         let bucket = crate::read_test_bucket().await;
@@ -1142,8 +985,9 @@ mod tests {
 
         let delete_result = Object::delete(&bucket.name, nonexistent_object).await;
 
-        if let Err(Error::Google(google_error_response)) = delete_result {
-            assert!(google_error_response.to_string().contains(&format!(
+        if let Err(Error::Http(http_error)) = delete_result {
+            assert_eq!(http_error.status, reqwest::StatusCode::NOT_FOUND);
+            assert!(http_error.to_string().contains(&format!(
                 "No such object: {}/{}",
                 bucket.name, nonexistent_object
             )));
@@ -1176,7 +1020,7 @@ mod tests {
             destination: None,
         };
         let obj3 = Object::compose(&bucket.name, &compose_request, "test-concatted-file").await?;
-        let url = obj3.download_url(100)?;
+        let url = download_url(&obj3).await?;
         let content = reqwest::get(&url).await?.text().await?;
         assert_eq!(content.as_bytes(), &[0, 1, 2, 3]);
         Ok(())
@@ -1195,7 +1039,7 @@ mod tests {
         let bucket = crate::read_test_bucket().await;
         let obj = Object::create(&bucket.name, vec![0, 1], "test-rewrite", "text/plain").await?;
         let obj = obj.rewrite(&bucket.name, "test-rewritten").await?;
-        let url = obj.download_url(100)?;
+        let url = download_url(&obj).await?;
         let client = reqwest::Client::default();
         let download = client.head(&url).send().await?;
         assert_eq!(download.status().as_u16(), 200);
@@ -1215,8 +1059,8 @@ mod tests {
         ];
         for name in &complicated_names {
             let _obj = Object::create(&bucket.name, vec![0, 1], name, "text/plain").await?;
-            let obj = Object::read(&bucket.name, &name).await.unwrap();
-            let url = obj.download_url(100)?;
+            let obj = Object::read(&bucket.name, name).await.unwrap();
+            let url = download_url(&obj).await?;
             let client = reqwest::Client::default();
             let download = client.head(&url).send().await?;
             assert_eq!(download.status().as_u16(), 200);
@@ -1231,7 +1075,10 @@ mod tests {
         let obj = Object::create(&bucket.name, vec![0, 1], "test-rewrite", "text/plain").await?;
 
         let opts1 = crate::DownloadOptions::new().content_disposition("attachment");
-        let download_url1 = obj.download_url_with(100, opts1)?;
+        let download_url1 = crate::CLOUD_CLIENT
+            .object()
+            .download_url_with(&obj, 100, opts1)
+            .await?;
         let download1 = client.head(&download_url1).send().await?;
         assert_eq!(download1.headers()["content-disposition"], "attachment");
         Ok(())
@@ -1244,7 +1091,7 @@ mod tests {
         let blob_name = "test-upload-url";
         let obj = Object::create(&bucket.name, vec![0, 1], blob_name, "text/plain").await?;
 
-        let url = obj.upload_url(100).unwrap();
+        let url = crate::CLOUD_CLIENT.object().upload_url(&obj, 100).await?;
         let updated_content = vec![2, 3];
         let response = client
             .put(&url)
@@ -1266,7 +1113,10 @@ mod tests {
         let mut custom_metadata = HashMap::new();
         custom_metadata.insert(String::from("field"), String::from("value"));
 
-        let (url, headers) = obj.upload_url_with(100, custom_metadata).unwrap();
+        let (url, headers) = crate::CLOUD_CLIENT
+            .object()
+            .upload_url_with(&obj, 100, custom_metadata)
+            .await?;
         let updated_content = vec![2, 3];
         let mut request = client.put(&url).body(updated_content);
         for (metadata_field, metadata_value) in headers.iter() {
@@ -1280,20 +1130,6 @@ mod tests {
         Ok(())
     }
 
-    #[cfg(all(feature = "openssl", feature = "ring"))]
-    #[test]
-    fn check_matching_crypto() {
-        assert_eq!(
-            openssl::sha256(b"hello").as_ref(),
-            ring::sha256(b"hello").as_ref()
-        );
-
-        assert_eq!(
-            openssl::rsa_pkcs1_sha256("world").unwrap(),
-            ring::rsa_pkcs1_sha256("world").unwrap(),
-        );
-    }
-
     #[cfg(feature = "sync")]
     mod sync {
         use super::*;
@@ -1444,8 +1280,9 @@ mod tests {
 
             let delete_result = Object::delete_sync(&bucket.name, nonexistent_object);
 
-            if let Err(Error::Google(google_error_response)) = delete_result {
-                assert!(google_error_response.to_string().contains(&format!(
+            if let Err(Error::Http(http_error)) = delete_result {
+                assert_eq!(http_error.status, reqwest::StatusCode::NOT_FOUND);
+                assert!(http_error.to_string().contains(&format!(
                     "No such object: {}/{}",
                     bucket.name, nonexistent_object
                 )));
@@ -1480,7 +1317,7 @@ mod tests {
                 destination: None,
             };
             let obj3 = Object::compose_sync(&bucket.name, &compose_request, "test-concatted-file")?;
-            let url = obj3.download_url(100)?;
+            let url = crate::runtime()?.block_on(super::download_url(&obj3))?;
             let content = reqwest::blocking::get(&url)?.text()?;
             assert_eq!(content.as_bytes(), &[0, 1, 2, 3]);
             Ok(())
@@ -1500,7 +1337,7 @@ mod tests {
             let bucket = crate::read_test_bucket_sync();
             let obj = Object::create_sync(&bucket.name, vec![0, 1], "test-rewrite", "text/plain")?;
             let obj = obj.rewrite_sync(&bucket.name, "test-rewritten")?;
-            let url = obj.download_url(100)?;
+            let url = crate::runtime()?.block_on(super::download_url(&obj))?;
             let client = reqwest::blocking::Client::new();
             let download = client.head(&url).send()?;
             assert_eq!(download.status().as_u16(), 200);
@@ -1520,8 +1357,8 @@ mod tests {
             ];
             for name in &complicated_names {
                 let _obj = Object::create_sync(&bucket.name, vec![0, 1], name, "text/plain")?;
-                let obj = Object::read_sync(&bucket.name, &name).unwrap();
-                let url = obj.download_url(100)?;
+                let obj = Object::read_sync(&bucket.name, name).unwrap();
+                let url = crate::runtime()?.block_on(super::download_url(&obj))?;
                 let client = reqwest::blocking::Client::new();
                 let download = client.head(&url).send()?;
                 assert_eq!(download.status().as_u16(), 200);
@@ -1560,3 +1397,107 @@ impl<S: Stream<Item = crate::Result<u8>> + Unpin> Stream for SizedByteStream<S>
         (size.unwrap_or(0), size)
     }
 }
+
+#[cfg(test)]
+mod signed_url_tests {
+    use super::*;
+    use crate::resources::service_account::ServiceAccount;
+
+    fn object() -> Object {
+        serde_json::from_value(serde_json::json!({
+            "kind": "storage#object",
+            "id": "test-bucket/dir/my file.txt/1549875600000000",
+            "selfLink": "https://www.googleapis.com/storage/v1/b/test-bucket/o/dir%2Fmy%20file.txt",
+            "name": "dir/my file.txt",
+            "bucket": "test-bucket",
+            "generation": "1549875600000000",
+            "metageneration": "1",
+            "timeCreated": "2019-02-01T09:00:00Z",
+            "updated": "2019-02-01T09:00:00Z",
+            "storageClass": "STANDARD",
+            "timeStorageClassUpdated": "2019-02-01T09:00:00Z",
+            "size": "5",
+            "mediaLink": "https://storage.googleapis.com/download/storage/v1/b/test-bucket/o/dir%2Fmy%20file.txt?alt=media",
+            "crc32c": "mnG7TA==",
+            "etag": "CICg7ND6nuACEAE=",
+        }))
+        .unwrap()
+    }
+
+    // Signs the url of `builder` as the test service account, at a fixed moment.
+    async fn sign(builder: SignedUrlBuilder) -> crate::Result<String> {
+        let signer = crate::ServiceAccountSigner::new(ServiceAccount::test());
+        builder
+            .issued_at("2019-02-01T09:00:00Z".parse().unwrap())
+            .sign(&signer)
+            .await
+    }
+
+    // The expected urls were computed outside of this crate with the key of the test service
+    // account.
+    const URL: &str = "https://storage.googleapis.com/test-bucket/dir/my%20file.txt\
+        ?X-Goog-Algorithm=GOOG4-RSA-SHA256\
+        &X-Goog-Credential=test%40cloud-storage-rs-test.iam.gserviceaccount.com%2F20190201%2Fauto%2Fstorage%2Fgoog4_request\
+        &X-Goog-Date=20190201T090000Z&X-Goog-Expires=100";
+
+    #[tokio::test]
+    async fn download_url() -> Result<(), Box<dyn std::error::Error>> {
+        let url = sign(object().signed_url(100, "GET", None, &HashMap::new())).await?;
+        assert_eq!(
+            url,
+            format!(
+                "{}&X-Goog-SignedHeaders=host&X-Goog-Signature={}",
+                URL,
+                "9e663b2df5f91cb59f8584c051295d9b0307b3e65f2ebec533fd97594c87dca9ffbb87a86b353394764d81f8b1ba48706ec3e15844f3629a58921ad345e1b4abc4c5107595086f6f74ccfd58d12db90f70446a123a749ccc7dec9207696a634f47bea15437cca5903a07f10842172d933c3ec80b16633baccfefdc578bfa347d75721d39c6df08c4828c4a84e11276c46645200f32fdb3899eb9196e4af237518fd4d0b4449e8d551b5ebbd7072634a18d4109759971706c4700cce5b612a625eb02fae1a93045ca8c38e48dcc2cdbd4b83bcac5368fa7991a66aec751dfc70feafb3f94952f3d700582a62a24e770bdd747d1e5c9b89a279e096304d94416eb"
+            )
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_url_with_content_disposition() -> Result<(), Box<dyn std::error::Error>> {
+        let disposition = Some(r#"attachment; filename="my file.txt""#.to_string());
+        let url = sign(object().signed_url(100, "GET", disposition, &HashMap::new())).await?;
+        assert_eq!(
+            url,
+            format!(
+                "{}&X-Goog-SignedHeaders=host\
+                &response-content-disposition=attachment%3B%20filename%3D%22my%20file.txt%22\
+                &X-Goog-Signature={}",
+                URL,
+                "169dfadfc0d08c433b82a8ff58de6660f19356409d313ebe2be72042bca372968028e8410abc18a28b97d4fe07aad965bf5675a5b9cf5cb837598e3a0afa7cfbd13e25a42879603143915ed9762ab8983516ba5b87e6cded8ae536fa709d0cf15b112bed2d396bf723e1f36d7ea02945c8982712b0f7863ac2dbbfd1ff4694f87f3fee224542dca630129a4db27b95fe00d978aaf88e63f83ea75dd5686027b42e09a978d464b960ed322fd1c6c56e67c53537e787d30bfb6bd5f2dc5fbd69f191ba83a675a55eb6c601f51acf70c4d6977c9c0ee275f654a8a1b41b0f9d99dc9165c5fe6c2e55519c80ca75aaf66e924903dc804ca83108521e5246224bb3f4"
+            )
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upload_url() -> Result<(), Box<dyn std::error::Error>> {
+        let url = sign(object().signed_url(100, "PUT", None, &HashMap::new())).await?;
+        assert_eq!(
+            url,
+            format!(
+                "{}&X-Goog-SignedHeaders=host&X-Goog-Signature={}",
+                URL,
+                "448b4d09407e0681d7911d1d4ef867bfa8c6c01c5fd8749af5dc650664ad792736602cc138e36b8272e4dbdc3ebfeac54c81e209241d4e8be03fdc658d63db70653c4adda01ff822c9ee51c0bf10e98eddfa8b733777a27a87dc24ada8ad2932cb71d26f16f7f12e919fa9a7b783a24107cf1a8e20bc7080af138680e04229c3bbea83f93b92ef4f4297e732c6be88663620e2662babcb85066844515214c69ee7a472cbfebe3ce078146e6f73136a986ab2ccd1f515ae4aeb93f21f178469ce65be122a571d9b98e6c3e22f9a82136570dd54271d9ca20fa879b0e085f798c616519ceeef3069ae0daa40df78a52ead7a202b347d64e6503d27e10284926aac"
+            )
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upload_url_with_metadata() -> Result<(), Box<dyn std::error::Error>> {
+        let mut metadata = HashMap::new();
+        metadata.insert("field".to_string(), "value".to_string());
+        let url = sign(object().signed_url(100, "PUT", None, &metadata)).await?;
+        assert_eq!(
+            url,
+            format!(
+                "{}&X-Goog-SignedHeaders=host%3Bx-goog-meta-field&X-Goog-Signature={}",
+                URL,
+                "0b6d7239d54635bf32dc1b294a4ebbe3487c4aff6f9af6de9cf76620abaf37ab32e6c8d35ef752b9020f9a7dded6e641c3fc31474a087c7b6aaa2139cb025f7437fc04c1cc926bbf1a3c525457cc918af4c1e41d88bc4e484ca04a5203a5e54f10ab1ed4e382352dbf3383f1efaf9bc334dd869279375d75e29bd8fdfbfbb85b695694b4898d6f046be679584122634a3a77fa21f954c0f1e090a4a30969c25a7cee76a4dde51b8756b1736973a542c60ee6cbe7b616efb0d08ba6cc8a34691ee99e1d41c8a10bdfcca6e7b2e4c37cafea712165f17ab40cad83122c6ee69c4e6a6ca6f9ac9f1d57ddb1e6ef44544699a48d6106645717d05dbf98a19f2bb786"
+            )
+        );
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 /// A deserialized `service-account-********.json`-file.
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, Debug, Clone)]
 pub struct ServiceAccount {
     /// The type of authentication, this should always be `service_account`.
     #[serde(rename = "type")]
@@ -44,3 +44,19 @@ impl ServiceAccount {
         account
     }
 }
+
+#[cfg(test)]
+impl ServiceAccount {
+    /// A service account with a key that was generated for the tests of this crate only.
+    pub(crate) fn test() -> Self {
+        serde_json::from_str(include_str!("../../test-data/service-account.json")).unwrap()
+    }
+
+    /// The service account with which the cases of Google's conformance tests are signed.
+    pub(crate) fn conformance() -> Self {
+        serde_json::from_str(&crate::read_conformance_file(
+            "test_service_account.not-a-test.json",
+        ))
+        .unwrap()
+    }
+}
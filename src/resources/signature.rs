@@ -9,7 +9,7 @@ pub enum SignatureResponse {
 #[serde(rename_all = "camelCase")]
 pub struct SuccessResponse {
     pub key_id: String,
-    pub signature: String,
+    pub signed_blob: String,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -0,0 +1,295 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+/// The header with which Google Cloud Storage deduplicates retried requests.
+pub(crate) const IDEMPOTENCY_TOKEN: &str = "x-goog-gcs-idempotency-token";
+
+/// Determines whether, how often and how fast failed requests are retried by a
+/// [`Client`](crate::Client).
+///
+/// Requests are retried when they fail to connect, time out, or receive one of the status codes
+/// 408, 429, 500, 502, 503 or 504. By default only idempotent requests are retried: reads, and
+/// mutations that are guarded by an `ifGenerationMatch` or `ifMetagenerationMatch` precondition.
+/// Every request carries an `x-goog-gcs-idempotency-token` header, so that Google Cloud Storage
+/// can deduplicate retried mutations when `retry_mutations` is enabled. Requests with a streaming
+/// body are never retried.
+///
+/// ### Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+/// use cloud_storage::{Client, RetryPolicy};
+///
+/// let client = Client::builder()
+///     .retry_policy(
+///         RetryPolicy::new()
+///             .max_attempts(10)
+///             .deadline(Duration::from_secs(300)),
+///     )
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    deadline: Option<Duration>,
+    retry_mutations: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 6,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(32),
+            multiplier: 2.0,
+            jitter: true,
+            deadline: Some(Duration::from_secs(120)),
+            retry_mutations: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default policy, which makes at most 6 attempts within 120 seconds, waiting 1
+    /// second after the first attempt and doubling that up to 32 seconds after each next one.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// A policy that never retries.
+    pub fn never() -> Self {
+        Self::new().max_attempts(1)
+    }
+
+    /// Sets the maximum number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the time that is waited after the first failed attempt.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Sets the maximum time that is waited between two attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets the factor by which the backoff grows after each failed attempt. Factors below 1, and
+    /// factors that are not finite, are ignored.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        if multiplier.is_finite() && multiplier >= 1.0 {
+            self.multiplier = multiplier;
+        }
+        self
+    }
+
+    /// When enabled, which is the default, each backoff is a random duration between half of and
+    /// the full computed backoff, so that clients that failed at the same time do not retry at
+    /// the same time.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the time after the first attempt after which no new attempts are started, or `None`
+    /// to only limit the number of attempts.
+    pub fn deadline(mut self, deadline: impl Into<Option<Duration>>) -> Self {
+        self.deadline = deadline.into();
+        self
+    }
+
+    /// Also retries mutations that are not guarded by a precondition. Google Cloud Storage uses
+    /// the idempotency token of the request to avoid applying such a mutation twice.
+    pub fn retry_mutations(mut self, retry_mutations: bool) -> Self {
+        self.retry_mutations = retry_mutations;
+        self
+    }
+
+    /// Returns whether requests may be sent more than once at all.
+    pub(crate) fn retries(&self) -> bool {
+        self.max_attempts > 1
+    }
+
+    /// Returns whether `request` may be sent more than once.
+    pub(crate) fn may_retry(&self, request: &reqwest::Request) -> bool {
+        if !self.retries() {
+            return false;
+        }
+        let method = request.method();
+        if method == reqwest::Method::GET || method == reqwest::Method::HEAD {
+            return true;
+        }
+        self.retry_mutations
+            || request
+                .url()
+                .query_pairs()
+                .any(|(k, _)| k == "ifGenerationMatch" || k == "ifMetagenerationMatch")
+    }
+
+    /// Returns how long to wait before making attempt number `attempt + 1`, after attempt number
+    /// `attempt` failed `elapsed` after the first attempt was made, or `None` when no further
+    /// attempt should be made.
+    pub(crate) fn backoff(&self, attempt: u32, elapsed: Duration) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        // the factor overflows to infinity after enough attempts, so the backoff is only turned
+        // into a `Duration` once it is known to fit in one
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let backoff = Duration::try_from_secs_f64(secs)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        let backoff = if self.jitter {
+            backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+        } else {
+            backoff
+        };
+        match self.deadline {
+            Some(deadline) if elapsed + backoff >= deadline => None,
+            _ => Some(backoff),
+        }
+    }
+}
+
+/// Returns whether a response with this status code is worth retrying.
+pub(crate) fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// Returns whether a request that failed with this error is worth retrying.
+pub(crate) fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || error.is_request()
+}
+
+/// Returns a new random idempotency token, formatted as a version 4 UUID.
+pub(crate) fn idempotency_token() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::new().jitter(false).deadline(None);
+        let secs = |attempt| policy.backoff(attempt, Duration::ZERO).map(|d| d.as_secs());
+        assert_eq!(secs(1), Some(1));
+        assert_eq!(secs(2), Some(2));
+        assert_eq!(secs(3), Some(4));
+        assert_eq!(secs(5), Some(16));
+        assert_eq!(secs(6), None);
+        assert_eq!(
+            RetryPolicy::new()
+                .jitter(false)
+                .max_attempts(100)
+                .deadline(None)
+                .backoff(20, Duration::ZERO),
+            Some(Duration::from_secs(32))
+        );
+
+        let policy = RetryPolicy::new().jitter(false);
+        assert_eq!(
+            policy.backoff(1, Duration::from_secs(100)),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(policy.backoff(1, Duration::from_secs(119)), None);
+
+        let policy = RetryPolicy::new();
+        for _ in 0..100 {
+            let backoff = policy.backoff(2, Duration::ZERO).unwrap();
+            assert!(backoff >= Duration::from_secs(1) && backoff <= Duration::from_secs(2));
+        }
+    }
+
+    #[test]
+    fn backoff_of_many_attempts() {
+        let policy = RetryPolicy::new()
+            .max_attempts(u32::MAX)
+            .deadline(None)
+            .jitter(false);
+        for attempt in [64, 65, 1100, u32::MAX - 1] {
+            assert_eq!(
+                policy.backoff(attempt, Duration::ZERO),
+                Some(Duration::from_secs(32))
+            );
+        }
+        let policy = policy.jitter(true);
+        let backoff = policy.backoff(1000, Duration::ZERO).unwrap();
+        assert!(backoff >= Duration::from_secs(16) && backoff <= Duration::from_secs(32));
+    }
+
+    #[test]
+    fn bad_multiplier() {
+        for multiplier in [0.5, -2.0, f64::NAN, f64::INFINITY] {
+            let policy = RetryPolicy::new()
+                .multiplier(multiplier)
+                .max_attempts(100)
+                .deadline(None)
+                .jitter(false);
+            assert_eq!(
+                policy,
+                RetryPolicy::new()
+                    .max_attempts(100)
+                    .deadline(None)
+                    .jitter(false)
+            );
+            assert_eq!(
+                policy.backoff(3, Duration::ZERO),
+                Some(Duration::from_secs(4))
+            );
+        }
+        let policy = RetryPolicy::new().multiplier(1.0).jitter(false);
+        assert_eq!(
+            policy.backoff(5, Duration::ZERO),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn may_retry() {
+        let client = reqwest::Client::new();
+        let url = "https://storage.googleapis.com/storage/v1/b/bucket/o/object";
+        let policy = RetryPolicy::new();
+
+        assert!(policy.may_retry(&client.get(url).build().unwrap()));
+        assert!(!policy.may_retry(&client.delete(url).build().unwrap()));
+        let guarded = client.delete(url).query(&[("ifGenerationMatch", "1")]);
+        assert!(policy.may_retry(&guarded.build().unwrap()));
+        let post = client.post(url).build().unwrap();
+        assert!(!policy.may_retry(&post));
+        assert!(policy.clone().retry_mutations(true).may_retry(&post));
+        assert!(!RetryPolicy::never().may_retry(&client.get(url).build().unwrap()));
+    }
+
+    #[test]
+    fn idempotency_tokens() {
+        let token = idempotency_token();
+        assert_eq!(token.len(), 36);
+        assert_eq!(&token[14..15], "4");
+        assert_ne!(token, idempotency_token());
+    }
+}
@@ -0,0 +1,347 @@
+//! Signers that produce the signatures of signed urls.
+
+use crate::{
+    client::SendExt,
+    crypto,
+    resources::{service_account::ServiceAccount, signature::SuccessResponse},
+    token::TokenCache,
+};
+
+/// The algorithm with which a [`Signer`] signs, as it appears in the `X-Goog-Algorithm` parameter
+/// of a signed url.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningAlgorithm {
+    /// RSA signatures with SHA256, produced with the private key of a service account.
+    RsaSha256,
+    /// HMAC signatures with SHA256, produced with the secret of an HMAC key.
+    HmacSha256,
+}
+
+impl SigningAlgorithm {
+    /// The name of the algorithm, such as `GOOG4-RSA-SHA256`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SigningAlgorithm::RsaSha256 => "GOOG4-RSA-SHA256",
+            SigningAlgorithm::HmacSha256 => "GOOG4-HMAC-SHA256",
+        }
+    }
+}
+
+/// Trait that produces the signatures of signed urls. Implement this to sign with keys that are
+/// kept elsewhere, for example in a KMS or an HSM.
+#[async_trait::async_trait]
+pub trait Signer: Send + Sync {
+    /// The email address of the service account, or the access id of the HMAC key, on behalf of
+    /// which the signatures are produced.
+    fn client_email(&self) -> &str;
+
+    /// The algorithm with which this signer signs. The default implementation returns
+    /// `SigningAlgorithm::RsaSha256`.
+    fn algorithm(&self) -> SigningAlgorithm {
+        SigningAlgorithm::RsaSha256
+    }
+
+    /// Signs `message`. The `credential_scope` is the scope of the signed url, formatted as
+    /// `{date}/{location}/storage/goog4_request`, which HMAC signers use to derive their signing
+    /// key.
+    async fn sign(&self, credential_scope: &str, message: &[u8]) -> crate::Result<Vec<u8>>;
+}
+
+/// Signs locally with the private key of a service account.
+#[derive(Debug, Default)]
+pub struct ServiceAccountSigner {
+    // None means the service account found in the `SERVICE_ACCOUNT` environment parameter.
+    service_account: Option<ServiceAccount>,
+}
+
+impl ServiceAccountSigner {
+    /// Creates a signer that signs with the private key of `service_account`. The `Default`
+    /// implementation uses the service account found in the `SERVICE_ACCOUNT` environment
+    /// parameter instead.
+    pub fn new(service_account: ServiceAccount) -> Self {
+        Self {
+            service_account: Some(service_account),
+        }
+    }
+
+    fn service_account(&self) -> &ServiceAccount {
+        self.service_account
+            .as_ref()
+            .unwrap_or_else(|| &crate::SERVICE_ACCOUNT)
+    }
+}
+
+#[async_trait::async_trait]
+impl Signer for ServiceAccountSigner {
+    fn client_email(&self) -> &str {
+        &self.service_account().client_email
+    }
+
+    async fn sign(&self, _credential_scope: &str, message: &[u8]) -> crate::Result<Vec<u8>> {
+        crypto::rsa_pkcs1_sha256(&self.service_account().private_key, message)
+    }
+}
+
+// The endpoint of the IAM Service Account Credentials API
+const IAM_URL: &str = "https://iamcredentials.googleapis.com/v1";
+
+/// Lets the [IAM Service Account Credentials API](https://cloud.google.com/iam/docs/reference/credentials/rest/v1/projects.serviceAccounts/signBlob)
+/// sign on behalf of a service account. This does not require a private key, so it works in
+/// environments such as GKE with workload identity.
+///
+/// The account the token of this signer belongs to requires the `Service Account Token Creator`
+/// role on the signing service account. Requests to the IAM API are sent through a [`Client`], so
+/// they are retried and pass through its middleware like any other request.
+///
+/// [`Client`]: crate::Client
+#[derive(Debug)]
+pub struct IamSigner {
+    service_account_email: String,
+    client: crate::Client,
+    endpoint: String,
+}
+
+impl IamSigner {
+    /// Creates a signer that signs on behalf of the service account with the provided email
+    /// address, authenticating to the IAM API with the token of the service account attached to
+    /// the machine, which is fetched from the metadata server. See
+    /// [`MetadataToken`](crate::MetadataToken).
+    pub fn new(service_account_email: impl Into<String>) -> Self {
+        Self::with_cache(service_account_email, crate::MetadataToken::default())
+    }
+
+    /// The same as `IamSigner::new`, but authenticates to the IAM API with the provided token.
+    pub fn with_cache(
+        service_account_email: impl Into<String>,
+        token: impl TokenCache + Send + 'static,
+    ) -> Self {
+        let client = crate::Client::builder()
+            .token_cache(token)
+            .build_infallible();
+        Self::with_client(service_account_email, client)
+    }
+
+    /// The same as `IamSigner::new`, but sends the requests to the IAM API with `client`, using
+    /// its token, retry policy and middleware.
+    pub fn with_client(service_account_email: impl Into<String>, client: crate::Client) -> Self {
+        Self {
+            service_account_email: service_account_email.into(),
+            client,
+            endpoint: IAM_URL.to_string(),
+        }
+    }
+
+    /// Sets the endpoint of the IAM Service Account Credentials API, which defaults to
+    /// `https://iamcredentials.googleapis.com/v1`.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Signer for IamSigner {
+    fn client_email(&self) -> &str {
+        &self.service_account_email
+    }
+
+    async fn sign(&self, _credential_scope: &str, message: &[u8]) -> crate::Result<Vec<u8>> {
+        let url = format!(
+            "{}/projects/-/serviceAccounts/{}:signBlob",
+            self.endpoint,
+            crate::object::percent_encode(&self.service_account_email),
+        );
+        let body = serde_json::json!({ "payload": base64::encode(message) });
+        // signing the same payload twice yields the same signature, so this may be retried
+        let response: SuccessResponse = self
+            .client
+            .client
+            .post(&url)
+            .headers(self.client.get_headers().await?)
+            .json(&body)
+            .send_idempotent_with(&self.client)
+            .await?
+            .json()
+            .await?;
+        base64::decode(response.signed_blob).map_err(|e| crate::Error::Other(e.to_string()))
+    }
+}
+
+/// Signs with the secret of an [HMAC key](https://cloud.google.com/storage/docs/authentication/hmackeys).
+/// Urls signed with it use the `GOOG4-HMAC-SHA256` algorithm, which works where only HMAC keys are
+/// distributed. A signer can be made from a newly created [`HmacKey`](crate::hmac_key::HmacKey),
+/// which is the only time its secret is available.
+///
+/// ### Example
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use cloud_storage::{Client, HmacSigner};
+///
+/// let client = Client::builder()
+///     .signer(HmacSigner::new("GOOG1EXAMPLE", "my-secret"))
+///     .build()?;
+/// let url = client
+///     .object()
+///     .signed_download_url("my_bucket", "path/to/my/file.png", 50)
+///     .await?;
+///
+/// let hmac_key = client.hmac_key().create().await?;
+/// let signer = HmacSigner::from(&hmac_key);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HmacSigner {
+    access_id: String,
+    secret: String,
+}
+
+impl HmacSigner {
+    /// Creates a signer from the access id and the secret of an HMAC key.
+    pub fn new(access_id: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            access_id: access_id.into(),
+            secret: secret.into(),
+        }
+    }
+}
+
+impl From<&crate::hmac_key::HmacKey> for HmacSigner {
+    fn from(hmac_key: &crate::hmac_key::HmacKey) -> Self {
+        Self::new(&hmac_key.metadata.access_id, &hmac_key.secret)
+    }
+}
+
+#[async_trait::async_trait]
+impl Signer for HmacSigner {
+    fn client_email(&self) -> &str {
+        &self.access_id
+    }
+
+    fn algorithm(&self) -> SigningAlgorithm {
+        SigningAlgorithm::HmacSha256
+    }
+
+    async fn sign(&self, credential_scope: &str, message: &[u8]) -> crate::Result<Vec<u8>> {
+        // The signing key is derived by signing each component of the credential scope in turn,
+        // starting from the secret prefixed with `GOOG4`, or with `AWS4` for a scope that ends
+        // with `aws4_request`.
+        let prefix = match credential_scope.rsplit('/').next() {
+            Some("aws4_request") => "AWS4",
+            _ => "GOOG4",
+        };
+        let mut key = format!("{}{}", prefix, self.secret).into_bytes();
+        for component in credential_scope.split('/') {
+            key = crypto::hmac_sha256(&key, component.as_bytes())?;
+        }
+        crypto::hmac_sha256(&key, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        middleware::mock::{response, Canned, StaticToken},
+        resources::signature::SignatureResponse,
+    };
+
+    #[test]
+    fn parse_sign_blob_response() {
+        let success = r#"{ "keyId": "some-key", "signedBlob": "aGVsbG8=" }"#;
+        match serde_json::from_str(success).unwrap() {
+            SignatureResponse::Success(s) => assert_eq!(s.signed_blob, "aGVsbG8="),
+            SignatureResponse::Failure(f) => panic!("unexpected failure {:?}", f),
+        }
+
+        let failure = r#"{
+            "error": {
+                "code": 403,
+                "message": "The caller does not have permission",
+                "status": "PERMISSION_DENIED"
+            }
+        }"#;
+        match serde_json::from_str(failure).unwrap() {
+            SignatureResponse::Success(s) => panic!("unexpected success {:?}", s),
+            SignatureResponse::Failure(f) => assert_eq!(f.error.status, "PERMISSION_DENIED"),
+        }
+    }
+
+    #[tokio::test]
+    async fn iam_signer() -> Result<(), Box<dyn std::error::Error>> {
+        let canned = Canned::responses(vec![
+            response(503, &[], ""),
+            response(
+                200,
+                &[],
+                r#"{ "keyId": "key-1", "signedBlob": "c2lnbmVk" }"#,
+            ),
+            response(
+                403,
+                &[("content-type", "application/json")],
+                r#"{ "error": { "code": 403, "message": "denied", "errors": [] } }"#,
+            ),
+        ]);
+        let client = crate::Client::builder()
+            .token_cache(StaticToken("iam-token"))
+            .retry_policy(
+                crate::RetryPolicy::new().initial_backoff(std::time::Duration::from_millis(1)),
+            )
+            .middleware(canned.clone())
+            .build()?;
+        let signer = IamSigner::with_client("signer@project.iam.gserviceaccount.com", client)
+            .with_endpoint("http://iam.invalid/v1/");
+        assert_eq!(
+            signer.client_email(),
+            "signer@project.iam.gserviceaccount.com"
+        );
+
+        // signBlob is retried even though it is a POST without preconditions
+        assert_eq!(signer.sign("", b"hello").await?, b"signed");
+        let requests = canned.requests();
+        assert_eq!(requests.len(), 2);
+        let request = &requests[1];
+        assert_eq!(request.method, reqwest::Method::POST);
+        assert_eq!(
+            request.url.as_str(),
+            "http://iam.invalid/v1/projects/-/serviceAccounts/signer%40project.iam.gserviceaccount.com:signBlob"
+        );
+        assert_eq!(request.header("authorization"), Some("Bearer iam-token"));
+        let body: serde_json::Value = serde_json::from_slice(&request.body)?;
+        assert_eq!(body, serde_json::json!({ "payload": "aGVsbG8=" }));
+
+        let error = signer.sign("", b"hello").await.unwrap_err();
+        assert_eq!(error.status(), Some(reqwest::StatusCode::FORBIDDEN));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn service_account_signer() -> Result<(), Box<dyn std::error::Error>> {
+        let service_account = ServiceAccount::test();
+        let signer = ServiceAccountSigner::new(service_account.clone());
+        assert_eq!(signer.client_email(), service_account.client_email);
+        assert_eq!(signer.algorithm(), SigningAlgorithm::RsaSha256);
+
+        let signature = signer.sign("", b"hello").await?;
+        let expected = crypto::rsa_pkcs1_sha256(&service_account.private_key, b"hello")?;
+        assert_eq!(signature, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hmac_signer() -> Result<(), Box<dyn std::error::Error>> {
+        let signer = HmacSigner::new("GOOG1EXAMPLE", "secret");
+        assert_eq!(signer.client_email(), "GOOG1EXAMPLE");
+        assert_eq!(signer.algorithm(), SigningAlgorithm::HmacSha256);
+
+        // computed independently, by deriving the key from `GOOG4secret` and the components of
+        // the scope with HMAC-SHA256
+        let scope = "20200101/auto/storage/goog4_request";
+        assert_eq!(
+            hex::encode(signer.sign(scope, b"hello").await?),
+            "f28810877c5b2354f1ebf1009741d02064b45b230ab3b0d6940873b83e6e14cf"
+        );
+        Ok(())
+    }
+}
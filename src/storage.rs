@@ -0,0 +1,247 @@
+//! An object-safe abstraction over the bucket and object operations of Google Cloud Storage, so
+//! that code using it can be tested without access to a real bucket.
+
+use async_trait::async_trait;
+use futures_util::StreamExt;
+
+use crate::{
+    object::{ComposeRequest, ObjectList, Preconditions},
+    Bucket, Client, ListRequest, NewBucket, Object,
+};
+
+mod memory;
+
+#[cfg(any(test, feature = "testing"))]
+pub(crate) use memory::error;
+pub use memory::InMemoryStorage;
+
+/// The bucket and object operations of Google Cloud Storage. It is implemented by
+/// [`Client`](crate::Client), which talks to Google, and by [`InMemoryStorage`], which keeps
+/// everything in memory and is meant for unit tests. Code that accepts a `&dyn Storage` or an
+/// `Arc<dyn Storage>` can therefore be tested without a network connection.
+///
+/// Methods that operate on a single object take `Preconditions`, which for `compose_objects` and
+/// `copy_object` apply to the destination object.
+///
+/// ### Example
+/// ```
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use cloud_storage::{NewBucket, object::Preconditions};
+/// use cloud_storage::storage::{InMemoryStorage, Storage};
+///
+/// async fn save(storage: &dyn Storage, data: Vec<u8>) -> cloud_storage::Result<()> {
+///     storage
+///         .create_object("my_bucket", data, "file.txt", "text/plain", &Preconditions::default())
+///         .await?;
+///     Ok(())
+/// }
+///
+/// let storage = InMemoryStorage::new();
+/// storage.create_bucket(&NewBucket { name: "my_bucket".to_string(), ..Default::default() }).await?;
+/// save(&storage, b"hello".to_vec()).await?;
+/// let data = storage.download_object("my_bucket", "file.txt", &Preconditions::default()).await?;
+/// assert_eq!(data, b"hello");
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Creates a new bucket. See `BucketClient::create`.
+    async fn create_bucket(&self, new_bucket: &NewBucket) -> crate::Result<Bucket>;
+
+    /// Returns the bucket with the provided name. See `BucketClient::read`.
+    async fn read_bucket(&self, name: &str) -> crate::Result<Bucket>;
+
+    /// Returns all buckets of the project. See `BucketClient::list`.
+    async fn list_buckets(&self) -> crate::Result<Vec<Bucket>>;
+
+    /// Deletes an empty bucket. See `BucketClient::delete`.
+    async fn delete_bucket(&self, bucket: Bucket) -> crate::Result<()>;
+
+    /// Creates a new object, or a new generation of an existing one. See `ObjectClient::create`.
+    async fn create_object(
+        &self,
+        bucket: &str,
+        file: Vec<u8>,
+        filename: &str,
+        mime_type: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object>;
+
+    /// Returns the metadata of an object. See `ObjectClient::read`.
+    async fn read_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object>;
+
+    /// Returns the content of an object. See `ObjectClient::download`.
+    async fn download_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Vec<u8>>;
+
+    /// Updates the metadata of an object. See `ObjectClient::update`.
+    async fn update_object(
+        &self,
+        object: &Object,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object>;
+
+    /// Deletes an object. See `ObjectClient::delete`.
+    async fn delete_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<()>;
+
+    /// Returns a single page of the objects in a bucket. Pass the `next_page_token` of the result
+    /// as the `page_token` of `list_request` to obtain the next page. See `ObjectClient::list`.
+    async fn list_objects(
+        &self,
+        bucket: &str,
+        list_request: &ListRequest,
+    ) -> crate::Result<ObjectList>;
+
+    /// Concatenates the contents of multiple objects into one. See `ObjectClient::compose`.
+    async fn compose_objects(
+        &self,
+        bucket: &str,
+        req: &ComposeRequest,
+        destination_object: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object>;
+
+    /// Copies an object to the target bucket and path. See `ObjectClient::copy`.
+    async fn copy_object(
+        &self,
+        object: &Object,
+        destination_bucket: &str,
+        path: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object>;
+}
+
+#[async_trait]
+impl Storage for Client {
+    async fn create_bucket(&self, new_bucket: &NewBucket) -> crate::Result<Bucket> {
+        self.bucket().create(new_bucket).await
+    }
+
+    async fn read_bucket(&self, name: &str) -> crate::Result<Bucket> {
+        self.bucket().read(name).await
+    }
+
+    async fn list_buckets(&self) -> crate::Result<Vec<Bucket>> {
+        self.bucket().list().await
+    }
+
+    async fn delete_bucket(&self, bucket: Bucket) -> crate::Result<()> {
+        self.bucket().delete(bucket).await
+    }
+
+    async fn create_object(
+        &self,
+        bucket: &str,
+        file: Vec<u8>,
+        filename: &str,
+        mime_type: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        self.object()
+            .with_preconditions(preconditions.clone())
+            .create(bucket, file, filename, mime_type)
+            .await
+    }
+
+    async fn read_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        self.object()
+            .with_preconditions(preconditions.clone())
+            .read(bucket, file_name)
+            .await
+    }
+
+    async fn download_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Vec<u8>> {
+        self.object()
+            .with_preconditions(preconditions.clone())
+            .download(bucket, file_name)
+            .await
+    }
+
+    async fn update_object(
+        &self,
+        object: &Object,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        self.object()
+            .with_preconditions(preconditions.clone())
+            .update(object)
+            .await
+    }
+
+    async fn delete_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<()> {
+        self.object()
+            .with_preconditions(preconditions.clone())
+            .delete(bucket, file_name)
+            .await
+    }
+
+    async fn list_objects(
+        &self,
+        bucket: &str,
+        list_request: &ListRequest,
+    ) -> crate::Result<ObjectList> {
+        let pages = self.object().list(bucket, list_request.clone()).await?;
+        futures_util::pin_mut!(pages);
+        match pages.next().await {
+            Some(page) => page,
+            None => Ok(ObjectList::default()),
+        }
+    }
+
+    async fn compose_objects(
+        &self,
+        bucket: &str,
+        req: &ComposeRequest,
+        destination_object: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        self.object()
+            .with_preconditions(preconditions.clone())
+            .compose(bucket, req, destination_object)
+            .await
+    }
+
+    async fn copy_object(
+        &self,
+        object: &Object,
+        destination_bucket: &str,
+        path: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        self.object()
+            .with_preconditions(preconditions.clone())
+            .copy(object, destination_bucket, path)
+            .await
+    }
+}
@@ -0,0 +1,793 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+
+use super::Storage;
+use crate::{
+    bucket::StorageClass,
+    error::{ErrorList, GoogleError, GoogleErrorResponse, HttpError, Reason},
+    object::{percent_encode, ComposeRequest, ObjectList, Preconditions},
+    Bucket, ListRequest, NewBucket, Object,
+};
+
+/// The maximum number of entries `list_objects` returns in a single page.
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// The maximum number of source objects of a single compose request.
+const MAX_COMPOSE_SOURCES: usize = 32;
+
+/// A [`Storage`] that keeps its buckets and objects in memory, for use in unit tests.
+///
+/// Like Google Cloud Storage, every write of an object creates a new generation and every update
+/// of its metadata a new metageneration, and requests whose `Preconditions` do not hold fail with
+/// `412 Precondition Failed`. Missing buckets and objects result in `404 Not Found`, and creating a
+/// bucket that exists or deleting one that is not empty in `409 Conflict`. All of these are
+/// returned as an `Error::Http`. Only the live generation of each object is kept.
+#[derive(Debug, Default)]
+pub struct InMemoryStorage {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    generation: i64,
+    buckets: BTreeMap<String, StoredBucket>,
+}
+
+#[derive(Debug)]
+struct StoredBucket {
+    bucket: Bucket,
+    objects: BTreeMap<String, StoredObject>,
+}
+
+#[derive(Debug)]
+struct StoredObject {
+    object: Object,
+    data: Vec<u8>,
+}
+
+impl InMemoryStorage {
+    /// Creates a storage without any buckets.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        // a panic while holding the lock cannot leave the maps in an inconsistent state
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Creates a new generation of an object, of which the metadata is filled in by `init`.
+    pub(crate) fn create_with(
+        &self,
+        bucket: &str,
+        data: Vec<u8>,
+        name: &str,
+        preconditions: &Preconditions,
+        init: impl FnOnce(&mut Object),
+    ) -> crate::Result<Object> {
+        self.state().insert(bucket, name, data, preconditions, init)
+    }
+
+    /// Composes the source objects of `req`, ignoring its `destination`. The metadata of the
+    /// composed object is filled in by `init`.
+    pub(crate) fn compose_with(
+        &self,
+        bucket: &str,
+        req: &ComposeRequest,
+        destination_object: &str,
+        preconditions: &Preconditions,
+        init: impl FnOnce(&mut Object),
+    ) -> crate::Result<Object> {
+        if req.source_objects.is_empty() || req.source_objects.len() > MAX_COMPOSE_SOURCES {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                Reason::InvalidArgument,
+                "A compose request must have between 1 and 32 source objects.",
+            ));
+        }
+        let mut state = self.state();
+        let mut data = Vec::new();
+        let mut component_count = 0;
+        for source in &req.source_objects {
+            let stored = state.object(bucket, &source.name)?;
+            if source
+                .generation
+                .is_some_and(|g| g != stored.object.generation)
+            {
+                return Err(not_found(&format!(
+                    "No such object: {}/{}",
+                    bucket, source.name
+                )));
+            }
+            let precondition = source.object_preconditions.as_ref();
+            if precondition.is_some_and(|p| p.if_generation_match != stored.object.generation) {
+                return Err(precondition_failed());
+            }
+            data.extend_from_slice(&stored.data);
+            component_count += stored.object.component_count.unwrap_or(1);
+        }
+        state.insert(bucket, destination_object, data, preconditions, |object| {
+            init(object);
+            object.component_count = Some(component_count);
+        })
+    }
+
+    /// Updates the metadata of a bucket through `update`, which creates a new metageneration.
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn update_bucket_with(
+        &self,
+        name: &str,
+        update: impl FnOnce(&mut Bucket) -> crate::Result<()>,
+    ) -> crate::Result<Bucket> {
+        let mut state = self.state();
+        let bucket = &mut state.bucket_mut(name)?.bucket;
+        let mut updated = bucket.clone();
+        update(&mut updated)?;
+        updated.metageneration += 1;
+        updated.updated = chrono::Utc::now();
+        updated.etag = etag(1, updated.metageneration);
+        *bucket = updated.clone();
+        Ok(updated)
+    }
+}
+
+impl State {
+    fn next_generation(&mut self) -> i64 {
+        self.generation += 1;
+        self.generation
+    }
+
+    fn bucket(&self, name: &str) -> crate::Result<&StoredBucket> {
+        self.buckets
+            .get(name)
+            .ok_or_else(|| not_found(&format!("The specified bucket does not exist: {}", name)))
+    }
+
+    fn bucket_mut(&mut self, name: &str) -> crate::Result<&mut StoredBucket> {
+        self.buckets
+            .get_mut(name)
+            .ok_or_else(|| not_found(&format!("The specified bucket does not exist: {}", name)))
+    }
+
+    fn object(&self, bucket: &str, name: &str) -> crate::Result<&StoredObject> {
+        self.bucket(bucket)?
+            .objects
+            .get(name)
+            .ok_or_else(|| not_found(&format!("No such object: {}/{}", bucket, name)))
+    }
+
+    /// Stores `data` as a new generation of the object `name` in `bucket`, after checking
+    /// `preconditions` against its current generation. The metadata of the new generation is
+    /// filled in by `init`.
+    fn insert(
+        &mut self,
+        bucket: &str,
+        name: &str,
+        data: Vec<u8>,
+        preconditions: &Preconditions,
+        init: impl FnOnce(&mut Object),
+    ) -> crate::Result<Object> {
+        let current = self.bucket(bucket)?.objects.get(name);
+        check(preconditions, current.map(|o| &o.object))?;
+        let generation = self.next_generation();
+        let mut object = new_object(bucket, name, generation, &data);
+        init(&mut object);
+        self.bucket_mut(bucket)?.objects.insert(
+            name.to_string(),
+            StoredObject {
+                object: object.clone(),
+                data,
+            },
+        );
+        Ok(object)
+    }
+}
+
+#[async_trait]
+impl Storage for InMemoryStorage {
+    async fn create_bucket(&self, new_bucket: &NewBucket) -> crate::Result<Bucket> {
+        let mut state = self.state();
+        if state.buckets.contains_key(&new_bucket.name) {
+            return Err(error(
+                StatusCode::CONFLICT,
+                Reason::Conflict,
+                "You already own this bucket. Please select another name.",
+            ));
+        }
+        let now = chrono::Utc::now();
+        let bucket = Bucket {
+            kind: "storage#bucket".to_string(),
+            id: new_bucket.name.clone(),
+            self_link: format!("{}/b/{}", crate::BASE_URL, percent_encode(&new_bucket.name)),
+            project_number: 0,
+            name: new_bucket.name.clone(),
+            time_created: now,
+            updated: now,
+            default_event_based_hold: new_bucket.default_event_based_hold,
+            retention_policy: None,
+            metageneration: 1,
+            acl: None,
+            default_object_acl: None,
+            iam_configuration: new_bucket.iam_configuration.clone(),
+            encryption: new_bucket.encryption.clone(),
+            owner: None,
+            location: new_bucket.location.clone(),
+            location_type: "region".to_string(),
+            website: new_bucket.website.clone(),
+            logging: new_bucket.logging.clone(),
+            versioning: new_bucket.versioning.clone(),
+            cors: new_bucket.cors.clone(),
+            lifecycle: new_bucket.lifecycle.clone(),
+            labels: new_bucket.labels.clone(),
+            storage_class: new_bucket
+                .storage_class
+                .clone()
+                .unwrap_or(StorageClass::Standard),
+            billing: new_bucket.billing.clone(),
+            etag: etag(1, 1),
+        };
+        state.buckets.insert(
+            bucket.name.clone(),
+            StoredBucket {
+                bucket: bucket.clone(),
+                objects: BTreeMap::new(),
+            },
+        );
+        Ok(bucket)
+    }
+
+    async fn read_bucket(&self, name: &str) -> crate::Result<Bucket> {
+        Ok(self.state().bucket(name)?.bucket.clone())
+    }
+
+    async fn list_buckets(&self) -> crate::Result<Vec<Bucket>> {
+        let state = self.state();
+        Ok(state.buckets.values().map(|b| b.bucket.clone()).collect())
+    }
+
+    async fn delete_bucket(&self, bucket: Bucket) -> crate::Result<()> {
+        let mut state = self.state();
+        if !state.bucket(&bucket.name)?.objects.is_empty() {
+            return Err(error(
+                StatusCode::CONFLICT,
+                Reason::Conflict,
+                "The bucket you tried to delete is not empty.",
+            ));
+        }
+        state.buckets.remove(&bucket.name);
+        Ok(())
+    }
+
+    async fn create_object(
+        &self,
+        bucket: &str,
+        file: Vec<u8>,
+        filename: &str,
+        mime_type: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        self.create_with(bucket, file, filename, preconditions, |object| {
+            object.content_type = Some(mime_type.to_string())
+        })
+    }
+
+    async fn read_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        let state = self.state();
+        let stored = state.object(bucket, file_name)?;
+        check(preconditions, Some(&stored.object))?;
+        Ok(stored.object.clone())
+    }
+
+    async fn download_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Vec<u8>> {
+        let state = self.state();
+        let stored = state.object(bucket, file_name)?;
+        check(preconditions, Some(&stored.object))?;
+        Ok(stored.data.clone())
+    }
+
+    async fn update_object(
+        &self,
+        object: &Object,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        let mut state = self.state();
+        let stored = state
+            .bucket_mut(&object.bucket)?
+            .objects
+            .get_mut(&object.name)
+            .ok_or_else(|| {
+                not_found(&format!(
+                    "No such object: {}/{}",
+                    object.bucket, object.name
+                ))
+            })?;
+        check(preconditions, Some(&stored.object))?;
+        let current = &mut stored.object;
+        current.metageneration += 1;
+        current.updated = chrono::Utc::now();
+        current.etag = etag(current.generation, current.metageneration);
+        copy_metadata(object, current);
+        Ok(current.clone())
+    }
+
+    async fn delete_object(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<()> {
+        let mut state = self.state();
+        check(
+            preconditions,
+            Some(&state.object(bucket, file_name)?.object),
+        )?;
+        state.bucket_mut(bucket)?.objects.remove(file_name);
+        Ok(())
+    }
+
+    async fn list_objects(
+        &self,
+        bucket: &str,
+        list_request: &ListRequest,
+    ) -> crate::Result<ObjectList> {
+        let state = self.state();
+        let objects = &state.bucket(bucket)?.objects;
+        Ok(list(objects, list_request))
+    }
+
+    async fn compose_objects(
+        &self,
+        bucket: &str,
+        req: &ComposeRequest,
+        destination_object: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        self.compose_with(bucket, req, destination_object, preconditions, |object| {
+            if let Some(destination) = &req.destination {
+                copy_metadata(destination, object);
+            }
+        })
+    }
+
+    async fn copy_object(
+        &self,
+        object: &Object,
+        destination_bucket: &str,
+        path: &str,
+        preconditions: &Preconditions,
+    ) -> crate::Result<Object> {
+        let mut state = self.state();
+        let source = state.object(&object.bucket, &object.name)?;
+        let data = source.data.clone();
+        let source = source.object.clone();
+        state.insert(destination_bucket, path, data, preconditions, |copy| {
+            copy_metadata(&source, copy);
+            copy.component_count = source.component_count;
+        })
+    }
+}
+
+/// Returns an error if one of `preconditions` does not hold for `current`, which is `None` when
+/// the object does not exist.
+fn check(preconditions: &Preconditions, current: Option<&Object>) -> crate::Result<()> {
+    let generation = current.map_or(0, |o| o.generation);
+    let metageneration = current.map(|o| o.metageneration);
+    let holds = preconditions
+        .if_generation_match
+        .map_or(true, |g| g == generation)
+        && preconditions.if_generation_not_match != Some(generation)
+        && preconditions
+            .if_metageneration_match
+            .map_or(true, |m| Some(m) == metageneration)
+        && preconditions
+            .if_metageneration_not_match
+            .map_or(true, |m| Some(m) != metageneration);
+    if holds {
+        Ok(())
+    } else {
+        Err(precondition_failed())
+    }
+}
+
+/// Returns the page of `objects` that `list_request` asks for.
+fn list(objects: &BTreeMap<String, StoredObject>, list_request: &ListRequest) -> ObjectList {
+    let prefix = list_request.prefix.as_deref().unwrap_or("");
+    let delimiter = list_request.delimiter.as_deref().filter(|d| !d.is_empty());
+    let include_trailing = list_request.include_trailing_delimiter == Some(true);
+    let max_results = list_request
+        .max_results
+        .unwrap_or(MAX_PAGE_SIZE)
+        .min(MAX_PAGE_SIZE);
+
+    let mut result = ObjectList {
+        kind: "storage#objects".to_string(),
+        ..Default::default()
+    };
+    let mut last = None;
+    let mut entries = 0;
+    for (name, stored) in objects.range(prefix.to_string()..) {
+        if !name.starts_with(prefix) {
+            break;
+        }
+        if let Some(end) = &list_request.end_offset {
+            if name >= end {
+                break;
+            }
+        }
+        if list_request
+            .start_offset
+            .as_ref()
+            .is_some_and(|start| name < start)
+        {
+            continue;
+        }
+        if let Some(token) = &list_request.page_token {
+            // a page token that ends in the delimiter marks a prefix that was already returned
+            let skipped_prefix =
+                delimiter.is_some_and(|d| token.ends_with(d)) && name.starts_with(token.as_str());
+            if name <= token || skipped_prefix {
+                continue;
+            }
+        }
+
+        let folded = delimiter.and_then(|d| {
+            name[prefix.len()..]
+                .find(d)
+                .map(|i| &name[..prefix.len() + i + d.len()])
+        });
+        let is_new_prefix =
+            folded.is_some_and(|p| result.prefixes.last().map(String::as_str) != Some(p));
+        let is_item = folded.map_or(true, |p| include_trailing && p == name);
+        if !is_new_prefix && !is_item {
+            continue;
+        }
+        if entries >= max_results {
+            result.next_page_token = last;
+            break;
+        }
+        if let (true, Some(p)) = (is_new_prefix, folded) {
+            result.prefixes.push(p.to_string());
+            last = Some(p.to_string());
+            entries += 1;
+        }
+        if is_item {
+            result.items.push(stored.object.clone());
+            last = Some(name.clone());
+            entries += 1;
+        }
+    }
+    result
+}
+
+/// Copies the fields of an object that can be changed by `update_object`.
+pub(crate) fn copy_metadata(from: &Object, to: &mut Object) {
+    to.content_type = from.content_type.clone();
+    to.content_encoding = from.content_encoding.clone();
+    to.content_disposition = from.content_disposition.clone();
+    to.content_language = from.content_language.clone();
+    to.cache_control = from.cache_control.clone();
+    to.metadata = from.metadata.clone();
+    to.temporary_hold = from.temporary_hold;
+    to.event_based_hold = from.event_based_hold;
+}
+
+fn new_object(bucket: &str, name: &str, generation: i64, data: &[u8]) -> Object {
+    let now = chrono::Utc::now();
+    let path = format!("b/{}/o/{}", percent_encode(bucket), percent_encode(name));
+    Object {
+        kind: "storage#object".to_string(),
+        id: format!("{}/{}/{}", bucket, name, generation),
+        self_link: format!("{}/{}", crate::BASE_URL, path),
+        name: name.to_string(),
+        bucket: bucket.to_string(),
+        generation,
+        metageneration: 1,
+        content_type: None,
+        time_created: now,
+        updated: now,
+        time_deleted: None,
+        temporary_hold: None,
+        event_based_hold: None,
+        retention_expiration_time: None,
+        storage_class: "STANDARD".to_string(),
+        time_storage_class_updated: now,
+        size: data.len() as u64,
+        md5_hash: None,
+        media_link: format!(
+            "https://storage.googleapis.com/download/storage/v1/{}?generation={}&alt=media",
+            path, generation
+        ),
+        content_encoding: None,
+        content_disposition: None,
+        content_language: None,
+        cache_control: None,
+        metadata: None,
+        acl: None,
+        owner: None,
+        crc32c: base64::encode(crc32c(data).to_be_bytes()),
+        component_count: None,
+        etag: etag(generation, 1),
+        customer_encryption: None,
+        kms_key_name: None,
+    }
+}
+
+fn etag(generation: i64, metageneration: i64) -> String {
+    base64::encode(format!("{}/{}", generation, metageneration))
+}
+
+/// The CRC32C (Castagnoli) checksum of `data`, as described in RFC 4960, Appendix B.
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn not_found(message: &str) -> crate::Error {
+    error(StatusCode::NOT_FOUND, Reason::NotFound, message)
+}
+
+fn precondition_failed() -> crate::Error {
+    error(
+        StatusCode::PRECONDITION_FAILED,
+        Reason::ConditionNotMet,
+        "At least one of the pre-conditions you specified did not hold.",
+    )
+}
+
+/// Builds the error Google Cloud Storage would return.
+pub(crate) fn error(status: StatusCode, reason: Reason, message: &str) -> crate::Error {
+    let body = serde_json::json!({
+        "error": {
+            "errors": [{ "domain": "global", "reason": &reason, "message": message }],
+            "code": status.as_u16(),
+            "message": message,
+        }
+    });
+    HttpError {
+        status,
+        headers: Default::default(),
+        google: Some(GoogleErrorResponse {
+            error: ErrorList {
+                errors: vec![GoogleError {
+                    domain: "global".to_string(),
+                    reason,
+                    message: message.to_string(),
+                    location_type: None,
+                    location: None,
+                }],
+                code: status.as_u16(),
+                message: message.to_string(),
+            },
+        }),
+        #[cfg(feature = "xml")]
+        xml: None,
+        body: body.to_string(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn none() -> Preconditions {
+        Preconditions::default()
+    }
+
+    async fn storage_with(names: &[&str]) -> Result<InMemoryStorage, Box<dyn std::error::Error>> {
+        let storage = InMemoryStorage::new();
+        storage
+            .create_bucket(&NewBucket {
+                name: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await?;
+        for name in names {
+            storage
+                .create_object(
+                    "bucket",
+                    name.as_bytes().to_vec(),
+                    name,
+                    "text/plain",
+                    &none(),
+                )
+                .await?;
+        }
+        Ok(storage)
+    }
+
+    #[test]
+    fn crc32c() {
+        assert_eq!(super::crc32c(b"123456789"), 0xe306_9283);
+        assert_eq!(base64::encode(super::crc32c(b"").to_be_bytes()), "AAAAAA==");
+    }
+
+    #[tokio::test]
+    async fn generations_and_preconditions() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = storage_with(&[]).await?;
+        let only_new = Preconditions {
+            if_generation_match: Some(0),
+            ..Default::default()
+        };
+        let first = storage
+            .create_object("bucket", b"one".to_vec(), "file", "text/plain", &only_new)
+            .await?;
+        assert_eq!(first.metageneration, 1);
+        assert_eq!(first.content_type.as_deref(), Some("text/plain"));
+        let err = storage
+            .create_object("bucket", b"two".to_vec(), "file", "text/plain", &only_new)
+            .await
+            .unwrap_err();
+        assert!(err.is_precondition_failed());
+
+        let matching = Preconditions {
+            if_generation_match: Some(first.generation),
+            ..Default::default()
+        };
+        let second = storage
+            .create_object("bucket", b"two".to_vec(), "file", "text/plain", &matching)
+            .await?;
+        assert!(second.generation > first.generation);
+        assert_eq!(
+            storage.download_object("bucket", "file", &none()).await?,
+            b"two"
+        );
+
+        let mut updated = second.clone();
+        updated.cache_control = Some("no-cache".to_string());
+        let updated = storage.update_object(&updated, &none()).await?;
+        assert_eq!(updated.generation, second.generation);
+        assert_eq!(updated.metageneration, 2);
+        let stale = Preconditions {
+            if_metageneration_match: Some(1),
+            ..Default::default()
+        };
+        let err = storage.update_object(&updated, &stale).await.unwrap_err();
+        assert!(err.is_precondition_failed());
+
+        let err = storage
+            .delete_object("bucket", "file", &matching)
+            .await
+            .unwrap_err();
+        assert!(err.is_precondition_failed());
+        storage.delete_object("bucket", "file", &none()).await?;
+        let err = storage
+            .read_object("bucket", "file", &none())
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+        assert!(err
+            .google_response()
+            .unwrap()
+            .errors_has_reason(&Reason::NotFound));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_with_delimiter() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = storage_with(&["a/1", "a/2", "a/b/3", "b/", "b/4", "c", "d"]).await?;
+        let request = ListRequest {
+            delimiter: Some("/".to_string()),
+            ..Default::default()
+        };
+        let list = storage.list_objects("bucket", &request).await?;
+        let names: Vec<_> = list.items.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["c", "d"]);
+        assert_eq!(list.prefixes, ["a/", "b/"]);
+        assert_eq!(list.next_page_token, None);
+
+        let request = ListRequest {
+            prefix: Some("a/".to_string()),
+            ..request
+        };
+        let list = storage.list_objects("bucket", &request).await?;
+        let names: Vec<_> = list.items.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["a/1", "a/2"]);
+        assert_eq!(list.prefixes, ["a/b/"]);
+
+        let request = ListRequest {
+            prefix: Some("b".to_string()),
+            include_trailing_delimiter: Some(true),
+            ..request
+        };
+        let list = storage.list_objects("bucket", &request).await?;
+        let names: Vec<_> = list.items.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["b/"]);
+        assert_eq!(list.prefixes, ["b/"]);
+
+        let mut request = ListRequest {
+            delimiter: Some("/".to_string()),
+            max_results: Some(1),
+            ..Default::default()
+        };
+        let mut pages = Vec::new();
+        loop {
+            let list = storage.list_objects("bucket", &request).await?;
+            let entries = list.prefixes.into_iter();
+            pages.extend(entries.chain(list.items.into_iter().map(|o| o.name)));
+            match list.next_page_token {
+                Some(token) => request.page_token = Some(token),
+                None => break,
+            }
+        }
+        assert_eq!(pages, ["a/", "b/", "c", "d"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn compose_and_copy() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = storage_with(&["one", "two"]).await?;
+        let one = storage.read_object("bucket", "one", &none()).await?;
+        let source = |name: &str, generation| crate::object::SourceObject {
+            name: name.to_string(),
+            generation,
+            object_preconditions: None,
+        };
+        let request = ComposeRequest {
+            kind: "storage#composeRequest".to_string(),
+            source_objects: vec![source("one", Some(one.generation)), source("two", None)],
+            destination: None,
+        };
+        let composed = storage
+            .compose_objects("bucket", &request, "both", &none())
+            .await?;
+        assert_eq!(composed.component_count, Some(2));
+        assert_eq!(composed.size, 6);
+        assert_eq!(
+            storage.download_object("bucket", "both", &none()).await?,
+            b"onetwo"
+        );
+
+        let request = ComposeRequest {
+            source_objects: vec![source("one", Some(one.generation + 100))],
+            ..request
+        };
+        let err = storage
+            .compose_objects("bucket", &request, "both", &none())
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+
+        let copy = storage
+            .copy_object(&composed, "bucket", "copy", &none())
+            .await?;
+        assert_eq!(copy.component_count, Some(2));
+        assert_eq!(copy.crc32c, composed.crc32c);
+        assert_eq!(
+            storage.download_object("bucket", "copy", &none()).await?,
+            b"onetwo"
+        );
+
+        let bucket = storage.read_bucket("bucket").await?;
+        let err = storage.delete_bucket(bucket.clone()).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+        for name in &["one", "two", "both", "copy"] {
+            storage.delete_object("bucket", name, &none()).await?;
+        }
+        storage.delete_bucket(bucket).await?;
+        assert!(storage.list_buckets().await?.is_empty());
+        Ok(())
+    }
+}
@@ -28,10 +28,10 @@ impl Client {
     /// credentials from the following locations:
     ///
     /// 1. Checks for the environment variable `SERVICE_ACCOUNT`, and if it exists, reads the file
-    /// at the path specified there as a credentials json file.
+    ///    at the path specified there as a credentials json file.
     /// 2. It attemps to do the same with the `GOOGLE_APPLICATION_CREDENTIALS` var.
     /// 3. It reads the `SERVICE_ACCOUNT_JSON` environment variable directly as json and uses that
-    /// 4.It attemps to do the same with the `GOOGLE_APPLICATION_CREDENTIALS_JSON` var.
+    /// 4. It attemps to do the same with the `GOOGLE_APPLICATION_CREDENTIALS_JSON` var.
     pub fn new() -> crate::Result<Self> {
         Ok(Self {
             runtime: crate::runtime()?,
@@ -47,33 +47,57 @@ impl Client {
         })
     }
 
+    /// Wraps an asynchronous client, for example one created with a
+    /// [`ClientBuilder`](crate::client::ClientBuilder).
+    pub(crate) fn from_client(client: crate::Client) -> crate::Result<Self> {
+        Ok(Self {
+            runtime: crate::runtime()?,
+            client,
+        })
+    }
+
+    /// Constructs a client that does not authenticate its requests, which allows reading from
+    /// public buckets without any credentials.
+    pub fn anonymous() -> crate::Result<Self> {
+        Ok(Self {
+            runtime: crate::runtime()?,
+            client: crate::Client::anonymous(),
+        })
+    }
+
+    /// Sets the signer that produces the signatures of the signed urls created by this client.
+    pub fn with_signer(mut self, signer: impl crate::Signer + 'static) -> Self {
+        self.client = self.client.with_signer(signer);
+        self
+    }
+
     /// Synchronous operations on [`Bucket`](crate::bucket::Bucket)s.
-    pub fn bucket(&self) -> BucketClient {
-        BucketClient(self)
+    pub fn bucket(&self) -> BucketClient<'_> {
+        BucketClient(self, None)
     }
 
     /// Synchronous operations on [`BucketAccessControl`](crate::bucket_access_control::BucketAccessControl)s.
-    pub fn bucket_access_control(&self) -> BucketAccessControlClient {
-        BucketAccessControlClient(self)
+    pub fn bucket_access_control(&self) -> BucketAccessControlClient<'_> {
+        BucketAccessControlClient(self, None)
     }
 
     /// Synchronous operations on [`DefaultObjectAccessControl`](crate::default_object_access_control::DefaultObjectAccessControl)s.
-    pub fn default_object_access_control(&self) -> DefaultObjectAccessControlClient {
-        DefaultObjectAccessControlClient(self)
+    pub fn default_object_access_control(&self) -> DefaultObjectAccessControlClient<'_> {
+        DefaultObjectAccessControlClient(self, None)
     }
 
     /// Synchronous operations on [`HmacKey`](crate::hmac_key::HmacKey)s.
-    pub fn hmac_key(&self) -> HmacKeyClient {
-        HmacKeyClient(self)
+    pub fn hmac_key(&self) -> HmacKeyClient<'_> {
+        HmacKeyClient(self, None)
     }
 
     /// Synchronous operations on [`Object`](crate::object::Object)s.
-    pub fn object(&self) -> ObjectClient {
-        ObjectClient(self)
+    pub fn object(&self) -> ObjectClient<'_> {
+        ObjectClient(self, None)
     }
 
     /// Synchronous operations on [`ObjectAccessControl`](crate::object_access_control::ObjectAccessControl)s.
-    pub fn object_access_control(&self) -> ObjectAccessControlClient {
-        ObjectAccessControlClient(self)
+    pub fn object_access_control(&self) -> ObjectAccessControlClient<'_> {
+        ObjectAccessControlClient(self, None)
     }
 }
@@ -5,9 +5,38 @@ use crate::{
 
 /// Operations on [`Bucket`]()s.
 #[derive(Debug)]
-pub struct BucketClient<'a>(pub(super) &'a super::Client);
+pub struct BucketClient<'a>(pub(super) &'a super::Client, pub(super) Option<String>);
 
 impl<'a> BucketClient<'a> {
+    /// Bills the requests made by this client, including the IAM operations, to `project`. This
+    /// overrides the project set with `ClientBuilder::user_project`.
+    /// ### Example
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::sync::Client;
+    ///
+    /// let client = Client::new()?;
+    /// let bucket = client
+    ///     .bucket()
+    ///     .with_user_project("my-project")
+    ///     .read("requester-pays-bucket")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = Some(project.into());
+        self
+    }
+
+    /// The asynchronous client that the requests of this client are made with.
+    fn client(&self) -> crate::client::BucketClient<'_> {
+        let client = self.0.client.bucket();
+        match &self.1 {
+            Some(project) => client.with_user_project(project.clone()),
+            None => client,
+        }
+    }
+
     /// Creates a new `Bucket`. There are many options that you can provide for creating a new
     /// bucket, so the `NewBucket` resource contains all of them. Note that `NewBucket` implements
     /// `Default`, so you don't have to specify the fields you're not using. And error is returned
@@ -31,9 +60,7 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub fn create(&self, new_bucket: &NewBucket) -> crate::Result<Bucket> {
-        self.0
-            .runtime
-            .block_on(self.0.client.bucket().create(new_bucket))
+        self.0.runtime.block_on(self.client().create(new_bucket))
     }
 
     /// Returns all `Bucket`s within this project.
@@ -53,7 +80,7 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub fn list(&self) -> crate::Result<Vec<Bucket>> {
-        self.0.runtime.block_on(self.0.client.bucket().list())
+        self.0.runtime.block_on(self.client().list())
     }
 
     /// Returns a single `Bucket` by its name. If the Bucket does not exist, an error is returned.
@@ -77,7 +104,7 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub fn read(&self, name: &str) -> crate::Result<Bucket> {
-        self.0.runtime.block_on(self.0.client.bucket().read(name))
+        self.0.runtime.block_on(self.client().read(name))
     }
 
     /// Update an existing `Bucket`. If you declare you bucket as mutable, you can edit its fields.
@@ -108,9 +135,7 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub fn update(&self, bucket: &Bucket) -> crate::Result<Bucket> {
-        self.0
-            .runtime
-            .block_on(self.0.client.bucket().update(bucket))
+        self.0.runtime.block_on(self.client().update(bucket))
     }
 
     /// Delete an existing `Bucket`. This permanently removes a bucket from Google Cloud Storage.
@@ -136,9 +161,7 @@ impl<'a> BucketClient<'a> {
     /// # }
     /// ```
     pub fn delete(&self, bucket: Bucket) -> crate::Result<()> {
-        self.0
-            .runtime
-            .block_on(self.0.client.bucket().delete(bucket))
+        self.0.runtime.block_on(self.client().delete(bucket))
     }
 
     /// Returns the [IAM Policy](https://cloud.google.com/iam/docs/) for this bucket.
@@ -165,7 +188,7 @@ impl<'a> BucketClient<'a> {
     pub fn get_iam_policy(&self, bucket: &Bucket) -> crate::Result<IamPolicy> {
         self.0
             .runtime
-            .block_on(self.0.client.bucket().get_iam_policy(bucket))
+            .block_on(self.client().get_iam_policy(bucket))
     }
 
     /// Updates the [IAM Policy](https://cloud.google.com/iam/docs/) for this bucket.
@@ -204,7 +227,7 @@ impl<'a> BucketClient<'a> {
     pub fn set_iam_policy(&self, bucket: &Bucket, iam: &IamPolicy) -> crate::Result<IamPolicy> {
         self.0
             .runtime
-            .block_on(self.0.client.bucket().set_iam_policy(bucket, iam))
+            .block_on(self.client().set_iam_policy(bucket, iam))
     }
 
     /// Checks whether the user provided in the service account has this permission.
@@ -225,11 +248,8 @@ impl<'a> BucketClient<'a> {
         bucket: &Bucket,
         permission: &str,
     ) -> crate::Result<TestIamPermission> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .bucket()
-                .test_iam_permission(bucket, permission),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().test_iam_permission(bucket, permission))
     }
 }
@@ -2,9 +2,25 @@ use crate::bucket_access_control::{BucketAccessControl, Entity, NewBucketAccessC
 
 /// Operations on [`BucketAccessControl`](BucketAccessControl)s.
 #[derive(Debug)]
-pub struct BucketAccessControlClient<'a>(pub(super) &'a super::Client);
+pub struct BucketAccessControlClient<'a>(pub(super) &'a super::Client, pub(super) Option<String>);
 
 impl<'a> BucketAccessControlClient<'a> {
+    /// Bills the requests made by this client to `project`, which is needed to manage the access
+    /// controls of a requester pays bucket. See `BucketClient::with_user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = Some(project.into());
+        self
+    }
+
+    /// The asynchronous client that the requests of this client are made with.
+    fn client(&self) -> crate::client::BucketAccessControlClient<'_> {
+        let client = self.0.client.bucket_access_control();
+        match &self.1 {
+            Some(project) => client.with_user_project(project.clone()),
+            None => client,
+        }
+    }
+
     /// Create a new `BucketAccessControl` using the provided `NewBucketAccessControl`, related to
     /// the `Bucket` provided by the `bucket_name` argument.
     ///
@@ -33,12 +49,9 @@ impl<'a> BucketAccessControlClient<'a> {
         bucket: &str,
         new_bucket_access_control: &NewBucketAccessControl,
     ) -> crate::Result<BucketAccessControl> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .bucket_access_control()
-                .create(bucket, new_bucket_access_control),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().create(bucket, new_bucket_access_control))
     }
 
     /// Returns all `BucketAccessControl`s related to this bucket.
@@ -59,9 +72,7 @@ impl<'a> BucketAccessControlClient<'a> {
     /// # }
     /// ```
     pub fn list(&self, bucket: &str) -> crate::Result<Vec<BucketAccessControl>> {
-        self.0
-            .runtime
-            .block_on(self.0.client.bucket_access_control().list(bucket))
+        self.0.runtime.block_on(self.client().list(bucket))
     }
 
     /// Returns the ACL entry for the specified entity on the specified bucket.
@@ -82,9 +93,7 @@ impl<'a> BucketAccessControlClient<'a> {
     /// # }
     /// ```
     pub fn read(&self, bucket: &str, entity: &Entity) -> crate::Result<BucketAccessControl> {
-        self.0
-            .runtime
-            .block_on(self.0.client.bucket_access_control().read(bucket, entity))
+        self.0.runtime.block_on(self.client().read(bucket, entity))
     }
 
     /// Update this `BucketAccessControl`.
@@ -110,12 +119,9 @@ impl<'a> BucketAccessControlClient<'a> {
         &self,
         bucket_access_control: &BucketAccessControl,
     ) -> crate::Result<BucketAccessControl> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .bucket_access_control()
-                .update(bucket_access_control),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().update(bucket_access_control))
     }
 
     /// Permanently deletes the ACL entry for the specified entity on the specified bucket.
@@ -137,11 +143,8 @@ impl<'a> BucketAccessControlClient<'a> {
     /// # }
     /// ```
     pub fn delete(&self, bucket_access_control: BucketAccessControl) -> crate::Result<()> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .bucket_access_control()
-                .delete(bucket_access_control),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().delete(bucket_access_control))
     }
 }
@@ -5,9 +5,29 @@ use crate::{
 
 /// Operations on [`DefaultObjectAccessControl`](DefaultObjectAccessControl)s.
 #[derive(Debug)]
-pub struct DefaultObjectAccessControlClient<'a>(pub(super) &'a super::Client);
+pub struct DefaultObjectAccessControlClient<'a>(
+    pub(super) &'a super::Client,
+    pub(super) Option<String>,
+);
 
 impl<'a> DefaultObjectAccessControlClient<'a> {
+    /// Bills the requests made by this client to `project`, which is needed to manage the
+    /// default object access controls of a requester pays bucket. See
+    /// `BucketClient::with_user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = Some(project.into());
+        self
+    }
+
+    /// The asynchronous client that the requests of this client are made with.
+    fn client(&self) -> crate::client::DefaultObjectAccessControlClient<'_> {
+        let client = self.0.client.default_object_access_control();
+        match &self.1 {
+            Some(project) => client.with_user_project(project.clone()),
+            None => client,
+        }
+    }
+
     /// Create a new `DefaultObjectAccessControl` entry on the specified bucket.
     /// ### Important
     /// Important: This method fails with a `400 Bad Request` response for buckets with uniform
@@ -36,12 +56,9 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
         bucket: &str,
         new_acl: &NewDefaultObjectAccessControl,
     ) -> crate::Result<DefaultObjectAccessControl> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .default_object_access_control()
-                .create(bucket, new_acl),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().create(bucket, new_acl))
     }
 
     /// Retrieves default object ACL entries on the specified bucket.
@@ -61,9 +78,7 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
     /// # }
     /// ```
     pub fn list(&self, bucket: &str) -> crate::Result<Vec<DefaultObjectAccessControl>> {
-        self.0
-            .runtime
-            .block_on(self.0.client.default_object_access_control().list(bucket))
+        self.0.runtime.block_on(self.client().list(bucket))
     }
 
     /// Read a single `DefaultObjectAccessControl`.
@@ -87,12 +102,7 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
     /// # }
     /// ```
     pub fn read(&self, bucket: &str, entity: &Entity) -> crate::Result<DefaultObjectAccessControl> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .default_object_access_control()
-                .read(bucket, entity),
-        )
+        self.0.runtime.block_on(self.client().read(bucket, entity))
     }
 
     /// Update the current `DefaultObjectAccessControl`.
@@ -117,12 +127,9 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
         &self,
         default_object_access_control: &DefaultObjectAccessControl,
     ) -> crate::Result<DefaultObjectAccessControl> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .default_object_access_control()
-                .update(default_object_access_control),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().update(default_object_access_control))
     }
 
     /// Delete this 'DefaultObjectAccessControl`.
@@ -146,11 +153,8 @@ impl<'a> DefaultObjectAccessControlClient<'a> {
         &self,
         default_object_access_control: DefaultObjectAccessControl,
     ) -> Result<(), crate::Error> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .default_object_access_control()
-                .delete(default_object_access_control),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().delete(default_object_access_control))
     }
 }
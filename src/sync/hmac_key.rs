@@ -2,10 +2,27 @@ use crate::hmac_key::{HmacKey, HmacMeta, HmacState};
 
 /// Operations on [`HmacKey`](HmacKey)s.
 #[derive(Debug)]
-pub struct HmacKeyClient<'a>(pub(super) &'a super::Client);
+pub struct HmacKeyClient<'a>(pub(super) &'a super::Client, pub(super) Option<String>);
 
 impl<'a> HmacKeyClient<'a> {
-    /// Creates a new HMAC key for the specified service account.
+    /// Bills the requests made by this client to `project` instead of the project set with
+    /// `ClientBuilder::user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = Some(project.into());
+        self
+    }
+
+    /// The asynchronous client that the requests of this client are made with.
+    fn client(&self) -> crate::client::HmacKeyClient<'_> {
+        let client = self.0.client.hmac_key();
+        match &self.1 {
+            Some(project) => client.with_user_project(project.clone()),
+            None => client,
+        }
+    }
+
+    /// Creates a new HMAC key for the service account set with
+    /// `ClientBuilder::service_account_email`.
     ///
     /// The authenticated user must have `storage.hmacKeys.create` permission for the project in
     /// which the key will be created.
@@ -27,7 +44,7 @@ impl<'a> HmacKeyClient<'a> {
     /// # }
     /// ```
     pub fn create(&self) -> crate::Result<HmacKey> {
-        self.0.runtime.block_on(self.0.client.hmac_key().create())
+        self.0.runtime.block_on(self.client().create())
     }
 
     /// Retrieves a list of HMAC keys matching the criteria. Since the HmacKey is secret, this does
@@ -51,7 +68,7 @@ impl<'a> HmacKeyClient<'a> {
     /// # }
     /// ```
     pub fn list(&self) -> crate::Result<Vec<HmacMeta>> {
-        self.0.runtime.block_on(self.0.client.hmac_key().list())
+        self.0.runtime.block_on(self.client().list())
     }
 
     /// Retrieves an HMAC key's metadata. Since the HmacKey is secret, this does not return a
@@ -74,9 +91,7 @@ impl<'a> HmacKeyClient<'a> {
     /// # Ok(())
     /// # }
     pub fn read(&self, access_id: &str) -> crate::Result<HmacMeta> {
-        self.0
-            .runtime
-            .block_on(self.0.client.hmac_key().read(access_id))
+        self.0.runtime.block_on(self.client().read(access_id))
     }
 
     /// Updates the state of an HMAC key. See the HMAC Key resource descriptor for valid states.
@@ -101,7 +116,7 @@ impl<'a> HmacKeyClient<'a> {
     pub fn update(&self, access_id: &str, state: HmacState) -> crate::Result<HmacMeta> {
         self.0
             .runtime
-            .block_on(self.0.client.hmac_key().update(access_id, state))
+            .block_on(self.client().update(access_id, state))
     }
 
     /// Deletes an HMAC key. Note that a key must be set to `Inactive` first.
@@ -123,8 +138,6 @@ impl<'a> HmacKeyClient<'a> {
     /// # Ok(())
     /// # }
     pub fn delete(&self, access_id: &str) -> crate::Result<()> {
-        self.0
-            .runtime
-            .block_on(self.0.client.hmac_key().delete(access_id))
+        self.0.runtime.block_on(self.client().delete(access_id))
     }
 }
@@ -3,12 +3,43 @@ use crate::{
     ListRequest, Object,
 };
 use futures_util::TryStreamExt;
+use std::collections::HashMap;
 
 /// Operations on [`Object`](Object)s.
 #[derive(Debug)]
-pub struct ObjectClient<'a>(pub(super) &'a super::Client);
+pub struct ObjectClient<'a>(pub(super) &'a super::Client, pub(super) Option<String>);
 
 impl<'a> ObjectClient<'a> {
+    /// Bills the requests made by this client to `project`, which is required to read from or
+    /// write to a bucket that has requester pays enabled. This overrides the project set with
+    /// `ClientBuilder::user_project`.
+    /// ### Example
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use cloud_storage::sync::Client;
+    ///
+    /// let client = Client::new()?;
+    /// let data = client
+    ///     .object()
+    ///     .with_user_project("my-project")
+    ///     .download("requester-pays-bucket", "path/to/my/file.png")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = Some(project.into());
+        self
+    }
+
+    /// The asynchronous client that the requests of this client are made with.
+    fn client(&self) -> crate::client::ObjectClient<'_> {
+        let client = self.0.client.object();
+        match &self.1 {
+            Some(project) => client.with_user_project(project.clone()),
+            None => client,
+        }
+    }
+
     /// Create a new object.
     /// Upload a file as that is loaded in memory to google cloud storage, where it will be
     /// interpreted according to the mime type you specified.
@@ -32,12 +63,9 @@ impl<'a> ObjectClient<'a> {
         filename: &str,
         mime_type: &str,
     ) -> crate::Result<Object> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .object()
-                .create(bucket, file, filename, mime_type),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().create(bucket, file, filename, mime_type))
     }
 
     /// Create a new object. This works in the same way as `ObjectClient::create`, except it does not need
@@ -56,9 +84,7 @@ impl<'a> ObjectClient<'a> {
         let stream = super::helpers::ReaderStream::new(file);
 
         self.0.runtime.block_on(
-            self.0
-                .client
-                .object()
+            self.client()
                 .create_streamed(bucket, stream, length, filename, mime_type),
         )
     }
@@ -81,7 +107,7 @@ impl<'a> ObjectClient<'a> {
         list_request: ListRequest,
     ) -> crate::Result<Vec<ObjectList>> {
         let rt = &self.0.runtime;
-        let listed = rt.block_on(self.0.client.object().list(bucket, list_request))?;
+        let listed = rt.block_on(self.client().list(bucket, list_request))?;
         rt.block_on(listed.try_collect())
     }
 
@@ -100,7 +126,7 @@ impl<'a> ObjectClient<'a> {
     pub fn read(&self, bucket: &str, file_name: &str) -> crate::Result<Object> {
         self.0
             .runtime
-            .block_on(self.0.client.object().read(bucket, file_name))
+            .block_on(self.client().read(bucket, file_name))
     }
 
     /// Download the content of the object with the specified name in the specified bucket.
@@ -118,7 +144,7 @@ impl<'a> ObjectClient<'a> {
     pub fn download(&self, bucket: &str, file_name: &str) -> crate::Result<Vec<u8>> {
         self.0
             .runtime
-            .block_on(self.0.client.object().download(bucket, file_name))
+            .block_on(self.client().download(bucket, file_name))
     }
 
     /// Obtains a single object with the specified name in the specified bucket.
@@ -136,9 +162,7 @@ impl<'a> ObjectClient<'a> {
     /// # }
     /// ```
     pub fn update(&self, object: &Object) -> crate::Result<Object> {
-        self.0
-            .runtime
-            .block_on(self.0.client.object().update(object))
+        self.0.runtime.block_on(self.client().update(object))
     }
 
     /// Deletes a single object with the specified name in the specified bucket.
@@ -156,7 +180,7 @@ impl<'a> ObjectClient<'a> {
     pub fn delete(&self, bucket: &str, file_name: &str) -> crate::Result<()> {
         self.0
             .runtime
-            .block_on(self.0.client.object().delete(bucket, file_name))
+            .block_on(self.client().delete(bucket, file_name))
     }
 
     /// Obtains a single object with the specified name in the specified bucket.
@@ -196,12 +220,9 @@ impl<'a> ObjectClient<'a> {
         req: &ComposeRequest,
         destination_object: &str,
     ) -> crate::Result<Object> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .object()
-                .compose(bucket, req, destination_object),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().compose(bucket, req, destination_object))
     }
 
     /// Copy this object to the target bucket and path
@@ -224,12 +245,9 @@ impl<'a> ObjectClient<'a> {
         destination_bucket: &str,
         path: &str,
     ) -> crate::Result<Object> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .object()
-                .copy(object, destination_bucket, path),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().copy(object, destination_bucket, path))
     }
 
     /// Moves a file from the current location to the target bucket and path.
@@ -239,6 +257,7 @@ impl<'a> ObjectClient<'a> {
     /// * Geographical Location,
     /// * Encryption,
     /// * Storage class.
+    ///
     /// These limitations mean that for now, the rewrite and the copy methods do the same thing.
     /// ### Example
     /// ```no_run
@@ -259,11 +278,112 @@ impl<'a> ObjectClient<'a> {
         destination_bucket: &str,
         path: &str,
     ) -> crate::Result<Object> {
+        self.0
+            .runtime
+            .block_on(self.client().rewrite(object, destination_bucket, path))
+    }
+
+    /// Signs the url built by `builder` with the [`Signer`](crate::Signer) of this client.
+    pub fn sign_url(&self, builder: &crate::SignedUrlBuilder) -> crate::Result<String> {
+        self.0.runtime.block_on(self.client().sign_url(builder))
+    }
+
+    /// Signs the policy document built by `builder` with the [`Signer`](crate::Signer) of this
+    /// client.
+    pub fn sign_post_policy(
+        &self,
+        builder: &crate::post_policy::PostPolicyBuilder,
+    ) -> crate::Result<crate::post_policy::PostPolicy> {
+        self.0
+            .runtime
+            .block_on(self.client().sign_post_policy(builder))
+    }
+
+    /// The same as [`ObjectClient::download_url`], but for the object `file_name` in `bucket`,
+    /// which does not have to be read first.
+    pub fn signed_download_url(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        duration: u32,
+    ) -> crate::Result<String> {
+        self.0.runtime.block_on(
+            self.client()
+                .signed_download_url(bucket, file_name, duration),
+        )
+    }
+
+    /// The same as [`ObjectClient::upload_url`], but for the object `file_name` in `bucket`,
+    /// which does not have to exist yet.
+    pub fn signed_upload_url(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        duration: u32,
+    ) -> crate::Result<String> {
+        self.0
+            .runtime
+            .block_on(self.client().signed_upload_url(bucket, file_name, duration))
+    }
+
+    /// The same as [`ObjectClient::upload_url`], but starts a resumable upload session instead.
+    /// Returns the url, together with the headers that must be included in the `POST` request.
+    pub fn signed_resumable_upload_url(
+        &self,
+        bucket: &str,
+        file_name: &str,
+        duration: u32,
+        mime_type: &str,
+    ) -> crate::Result<(String, HashMap<String, String>)> {
+        self.0.runtime.block_on(
+            self.client()
+                .signed_resumable_upload_url(bucket, file_name, duration, mime_type),
+        )
+    }
+
+    /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
+    /// which is valid for `duration` seconds, and lets the posessor download the file contents
+    /// without any authentication. The url is signed with the
+    /// [`Signer`](crate::Signer) of this client.
+    pub fn download_url(&self, object: &Object, duration: u32) -> crate::Result<String> {
+        self.0
+            .runtime
+            .block_on(self.client().download_url(object, duration))
+    }
+
+    /// The same as [`ObjectClient::download_url`], but with additional options for the download.
+    pub fn download_url_with(
+        &self,
+        object: &Object,
+        duration: u32,
+        opts: crate::DownloadOptions,
+    ) -> crate::Result<String> {
+        self.0
+            .runtime
+            .block_on(self.client().download_url_with(object, duration, opts))
+    }
+
+    /// Creates a [Signed Url](https://cloud.google.com/storage/docs/access-control/signed-urls)
+    /// which is valid for `duration` seconds, and lets the posessor upload data to a blob
+    /// without any authentication. The url is signed with the
+    /// [`Signer`](crate::Signer) of this client.
+    pub fn upload_url(&self, object: &Object, duration: u32) -> crate::Result<String> {
+        self.0
+            .runtime
+            .block_on(self.client().upload_url(object, duration))
+    }
+
+    /// The same as [`ObjectClient::upload_url`], but also lets the posessor set custom metadata.
+    /// Returns the url, together with the headers that must be included in the `PUT` request.
+    pub fn upload_url_with(
+        &self,
+        object: &Object,
+        duration: u32,
+        custom_metadata: HashMap<String, String>,
+    ) -> crate::Result<(String, HashMap<String, String>)> {
         self.0.runtime.block_on(
-            self.0
-                .client
-                .object()
-                .rewrite(object, destination_bucket, path),
+            self.client()
+                .upload_url_with(object, duration, custom_metadata),
         )
     }
 }
@@ -5,9 +5,25 @@ use crate::{
 
 /// Operations on [`ObjectAccessControl`](ObjectAccessControl)s.
 #[derive(Debug)]
-pub struct ObjectAccessControlClient<'a>(pub(super) &'a super::Client);
+pub struct ObjectAccessControlClient<'a>(pub(super) &'a super::Client, pub(super) Option<String>);
 
 impl<'a> ObjectAccessControlClient<'a> {
+    /// Bills the requests made by this client to `project`, which is needed to manage the access
+    /// controls of objects in a requester pays bucket. See `ObjectClient::with_user_project`.
+    pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
+        self.1 = Some(project.into());
+        self
+    }
+
+    /// The asynchronous client that the requests of this client are made with.
+    fn client(&self) -> crate::client::ObjectAccessControlClient<'_> {
+        let client = self.0.client.object_access_control();
+        match &self.1 {
+            Some(project) => client.with_user_project(project.clone()),
+            None => client,
+        }
+    }
+
     /// Creates a new ACL entry on the specified `object`.
     ///
     /// ### Important
@@ -20,13 +36,10 @@ impl<'a> ObjectAccessControlClient<'a> {
         object: &str,
         new_object_access_control: &NewObjectAccessControl,
     ) -> crate::Result<ObjectAccessControl> {
-        self.0
-            .runtime
-            .block_on(self.0.client.object_access_control().create(
-                bucket,
-                object,
-                new_object_access_control,
-            ))
+        self.0.runtime.block_on(
+            self.client()
+                .create(bucket, object, new_object_access_control),
+        )
     }
 
     /// Retrieves `ACL` entries on the specified object.
@@ -36,9 +49,7 @@ impl<'a> ObjectAccessControlClient<'a> {
     /// bucket-level access enabled. Use `Bucket::get_iam_policy` and `Bucket::set_iam_policy` to
     /// control access instead.
     pub fn list(&self, bucket: &str, object: &str) -> crate::Result<Vec<ObjectAccessControl>> {
-        self.0
-            .runtime
-            .block_on(self.0.client.object_access_control().list(bucket, object))
+        self.0.runtime.block_on(self.client().list(bucket, object))
     }
 
     /// Returns the `ACL` entry for the specified entity on the specified bucket.
@@ -53,12 +64,9 @@ impl<'a> ObjectAccessControlClient<'a> {
         object: &str,
         entity: &Entity,
     ) -> crate::Result<ObjectAccessControl> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .object_access_control()
-                .read(bucket, object, entity),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().read(bucket, object, entity))
     }
 
     /// Updates an ACL entry on the specified object.
@@ -71,12 +79,9 @@ impl<'a> ObjectAccessControlClient<'a> {
         &self,
         object_access_control: &ObjectAccessControl,
     ) -> crate::Result<ObjectAccessControl> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .object_access_control()
-                .update(object_access_control),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().update(object_access_control))
     }
 
     /// Permanently deletes the ACL entry for the specified entity on the specified object.
@@ -86,11 +91,8 @@ impl<'a> ObjectAccessControlClient<'a> {
     /// bucket-level access enabled. Use `Bucket::get_iam_policy` and `Bucket::set_iam_policy` to
     /// control access instead.
     pub fn delete(&self, object_access_control: ObjectAccessControl) -> crate::Result<()> {
-        self.0.runtime.block_on(
-            self.0
-                .client
-                .object_access_control()
-                .delete(object_access_control),
-        )
+        self.0
+            .runtime
+            .block_on(self.client().delete(object_access_control))
     }
 }
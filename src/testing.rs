@@ -0,0 +1,1514 @@
+//! An in-process fake of the Google Cloud Storage JSON API, so that code using a
+//! [`Client`](crate::Client) can be tested offline, through the same requests and responses that
+//! it would exchange with Google.
+//!
+//! ### Features
+//! This module requires that the feature flag `testing` is enabled in `Cargo.toml`.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use hyper::{
+    body::Bytes,
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE, LOCATION, RANGE},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Value};
+
+use crate::{
+    bucket::{
+        Billing, Cors, Encryption, IamConfiguration, Lifecycle, Location, Logging, RetentionPolicy,
+        StorageClass, Versioning, Website,
+    },
+    error::Reason,
+    object::{ComposeRequest, ObjectPrecondition, Preconditions, SourceObject},
+    storage::{self, InMemoryStorage, Storage},
+    Bucket, ClientBuilder, ListRequest, NewBucket, Object,
+};
+
+/// A Google Cloud Storage server that runs on a background thread and keeps its buckets and
+/// objects in an [`InMemoryStorage`]. It implements the part of the JSON API that this crate
+/// uses: creating, reading, updating, listing and deleting buckets and objects, media, multipart
+/// and resumable uploads, downloads, compose, copy and rewrite, access control lists, the IAM
+/// policies of buckets and HMAC keys. It also serves the downloads and uploads of signed urls,
+/// without checking their signatures, and grants every permission. Preconditions are read from the
+/// `ifGenerationMatch`, `ifGenerationNotMatch`, `ifMetagenerationMatch` and
+/// `ifMetagenerationNotMatch` query parameters. The server stops when it is dropped.
+///
+/// ### Example
+/// ```
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use cloud_storage::NewBucket;
+/// use cloud_storage::testing::FakeServer;
+///
+/// let server = FakeServer::start()?;
+/// let client = server.client()?;
+/// let new_bucket = NewBucket { name: "my_bucket".to_string(), ..Default::default() };
+/// client.bucket().create(&new_bucket).await?;
+/// client.object().create("my_bucket", b"hello".to_vec(), "file.txt", "text/plain").await?;
+/// assert_eq!(client.object().download("my_bucket", "file.txt").await?, b"hello");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FakeServer {
+    addr: SocketAddr,
+    storage: Arc<InMemoryStorage>,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl FakeServer {
+    /// Starts a server without any buckets on a free port of `127.0.0.1`.
+    pub fn start() -> crate::Result<Self> {
+        Self::with_storage(Default::default())
+    }
+
+    /// Starts a server on a free port of `127.0.0.1` that serves the contents of `storage`, which
+    /// allows preparing and inspecting the buckets and objects without going through HTTP.
+    pub fn with_storage(storage: Arc<InMemoryStorage>) -> crate::Result<Self> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            storage: storage.clone(),
+            endpoint: format!("http://{}", addr),
+            uploads: Default::default(),
+            acls: Default::default(),
+            policies: Default::default(),
+            hmac_keys: Default::default(),
+        });
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()?;
+        let server = {
+            let _context = runtime.enter();
+            Server::from_tcp(listener)
+                .map_err(|e| crate::Error::Other(e.to_string()))?
+                .serve(make_service_fn(move |_| {
+                    let shared = shared.clone();
+                    async move {
+                        Ok::<_, Infallible>(service_fn(move |request| {
+                            handle(shared.clone(), request)
+                        }))
+                    }
+                }))
+        };
+        let (shutdown, stopped) = tokio::sync::oneshot::channel::<()>();
+        std::thread::Builder::new()
+            .name("fake-gcs-server".to_string())
+            .spawn(move || {
+                runtime.block_on(async {
+                    tokio::select! {
+                        _ = server => {}
+                        _ = stopped => {}
+                    }
+                })
+            })?;
+
+        Ok(Self {
+            addr,
+            storage,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// The url of the server, such as `http://127.0.0.1:49152`.
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// The storage that holds the buckets and objects of this server.
+    pub fn storage(&self) -> &Arc<InMemoryStorage> {
+        &self.storage
+    }
+
+    /// A builder for a client that talks to this server, see `ClientBuilder::emulator_host`.
+    pub fn client_builder(&self) -> ClientBuilder {
+        ClientBuilder::new().emulator_host(&self.endpoint())
+    }
+
+    /// Builds a client that talks to this server.
+    pub fn client(&self) -> crate::Result<crate::Client> {
+        self.client_builder().build()
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    storage: Arc<InMemoryStorage>,
+    endpoint: String,
+    uploads: Mutex<HashMap<String, Upload>>,
+    /// The access control lists, keyed by their path, such as `b/bucket/acl`.
+    acls: Mutex<HashMap<String, Vec<Value>>>,
+    /// The IAM policies of the buckets that have one.
+    policies: Mutex<HashMap<String, Value>>,
+    /// The metadata of the HMAC keys, keyed by their access id.
+    hmac_keys: Mutex<BTreeMap<String, Value>>,
+}
+
+/// A resumable upload that has been started but not finished.
+#[derive(Debug)]
+struct Upload {
+    bucket: String,
+    name: String,
+    metadata: ObjectMetadata,
+    preconditions: Preconditions,
+    data: Vec<u8>,
+}
+
+/// The fields of an object that can be set when it is created or updated.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct ObjectMetadata {
+    name: Option<String>,
+    content_type: Option<String>,
+    content_encoding: Option<String>,
+    content_disposition: Option<String>,
+    content_language: Option<String>,
+    cache_control: Option<String>,
+    metadata: Option<HashMap<String, String>>,
+    temporary_hold: Option<bool>,
+    event_based_hold: Option<bool>,
+}
+
+impl ObjectMetadata {
+    fn apply(self, object: &mut Object) {
+        object.content_type = self.content_type;
+        object.content_encoding = self.content_encoding;
+        object.content_disposition = self.content_disposition;
+        object.content_language = self.content_language;
+        object.cache_control = self.cache_control;
+        object.metadata = self.metadata;
+        object.temporary_hold = self.temporary_hold;
+        object.event_based_hold = self.event_based_hold;
+    }
+}
+
+/// The fields of a bucket that can be set when it is created or updated.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct BucketMetadata {
+    name: String,
+    default_event_based_hold: Option<bool>,
+    #[serde(deserialize_with = "retention_policy")]
+    retention_policy: Option<RetentionPolicy>,
+    iam_configuration: Option<IamConfiguration>,
+    encryption: Option<Encryption>,
+    location: Location,
+    website: Option<Website>,
+    logging: Option<Logging>,
+    versioning: Option<Versioning>,
+    cors: Option<Vec<Cors>>,
+    lifecycle: Option<Lifecycle>,
+    labels: Option<HashMap<String, String>>,
+    storage_class: Option<StorageClass>,
+    billing: Option<Billing>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComposeBody {
+    source_objects: Vec<ComposeSource>,
+    destination: Option<ObjectMetadata>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComposeSource {
+    name: String,
+    #[serde(default, deserialize_with = "int")]
+    generation: Option<i64>,
+    object_preconditions: Option<ComposeSourcePreconditions>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComposeSourcePreconditions {
+    #[serde(default, deserialize_with = "int")]
+    if_generation_match: Option<i64>,
+}
+
+/// Google sends 64 bit integers as strings, but accepts both strings and numbers.
+fn int<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{Deserialize, Error};
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => n.as_i64().map(Some).ok_or_else(|| D::Error::custom(n)),
+        Some(Value::String(s)) => s.parse().map(Some).map_err(D::Error::custom),
+        Some(other) => Err(D::Error::custom(format!(
+            "expected an integer, got {}",
+            other
+        ))),
+    }
+}
+
+/// Google sends the retention period as a string, but accepts both strings and numbers.
+fn retention_policy<'de, D>(deserializer: D) -> Result<Option<RetentionPolicy>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{Deserialize, Error};
+    let mut value = Option::<Value>::deserialize(deserializer)?;
+    if let Some(policy) = value.as_mut() {
+        stringify(policy, &["retentionPeriod"]);
+    }
+    value
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(D::Error::custom)
+}
+
+async fn handle(shared: Arc<Shared>, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    Ok(route(&shared, request).await.unwrap_or_else(error_response))
+}
+
+async fn route(shared: &Shared, request: Request<Body>) -> crate::Result<Response<Body>> {
+    let (parts, body) = request.into_parts();
+    let body = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| crate::Error::Other(e.to_string()))?;
+    let query = parse_query(parts.uri.query().unwrap_or(""));
+    let header = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+
+    let path = parts.uri.path();
+    let (upload, path) = match (
+        path.strip_prefix("/upload/storage/v1/"),
+        path.strip_prefix("/storage/v1/"),
+    ) {
+        (Some(path), _) => (true, path),
+        (None, Some(path)) => (false, path),
+        (None, None) => return xml_request(shared, &parts, &query, body).await,
+    };
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned())
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let storage = &shared.storage;
+
+    match (upload, parts.method.as_str(), segments.as_slice()) {
+        (false, "GET", ["b"]) => {
+            let buckets = storage.list_buckets().await?;
+            let items = buckets.iter().map(bucket_json).collect::<Vec<_>>();
+            ok(json!({ "kind": "storage#buckets", "items": items }))
+        }
+        (false, "POST", ["b"]) => {
+            let metadata: BucketMetadata = parse_body(&body)?;
+            let new_bucket = NewBucket {
+                name: metadata.name,
+                default_event_based_hold: metadata.default_event_based_hold,
+                iam_configuration: metadata.iam_configuration,
+                encryption: metadata.encryption,
+                location: metadata.location,
+                website: metadata.website,
+                logging: metadata.logging,
+                versioning: metadata.versioning,
+                cors: metadata.cors,
+                lifecycle: metadata.lifecycle,
+                labels: metadata.labels,
+                storage_class: metadata.storage_class,
+                billing: metadata.billing,
+                ..Default::default()
+            };
+            ok(bucket_json(&storage.create_bucket(&new_bucket).await?))
+        }
+        (false, "GET", ["b", bucket]) => ok(bucket_json(&storage.read_bucket(bucket).await?)),
+        (false, "PUT", ["b", bucket]) | (false, "PATCH", ["b", bucket]) => {
+            let metadata: BucketMetadata = parse_body(&body)?;
+            let bucket = storage.update_bucket_with(bucket, |bucket| {
+                bucket.default_event_based_hold = metadata.default_event_based_hold;
+                bucket.retention_policy = metadata.retention_policy;
+                bucket.iam_configuration = metadata.iam_configuration;
+                bucket.encryption = metadata.encryption;
+                bucket.website = metadata.website;
+                bucket.logging = metadata.logging;
+                bucket.versioning = metadata.versioning;
+                bucket.cors = metadata.cors;
+                bucket.lifecycle = metadata.lifecycle;
+                bucket.labels = metadata.labels;
+                if let Some(storage_class) = metadata.storage_class {
+                    bucket.storage_class = storage_class;
+                }
+                bucket.billing = metadata.billing;
+                Ok(())
+            })?;
+            ok(bucket_json(&bucket))
+        }
+        (false, "DELETE", ["b", bucket]) => {
+            storage
+                .delete_bucket(storage.read_bucket(bucket).await?)
+                .await?;
+            let prefix = format!("b/{}/", bucket);
+            lock(&shared.acls).retain(|path, _| !path.starts_with(&prefix));
+            lock(&shared.policies).remove(*bucket);
+            Ok(no_content())
+        }
+        (false, "GET", ["b", bucket, "iam"]) => {
+            storage.read_bucket(bucket).await?;
+            let policies = lock(&shared.policies);
+            match policies.get(*bucket) {
+                Some(policy) => ok(policy.clone()),
+                None => ok(policy_json(bucket, json!([]))),
+            }
+        }
+        (false, "PUT", ["b", bucket, "iam"]) => {
+            storage.read_bucket(bucket).await?;
+            let policy: Value = parse_body(&body)?;
+            let bindings = policy.get("bindings").cloned().unwrap_or_else(|| json!([]));
+            let policy = policy_json(bucket, bindings);
+            lock(&shared.policies).insert(bucket.to_string(), policy.clone());
+            ok(policy)
+        }
+        (false, "GET", ["b", bucket, "iam", "testPermissions"]) => {
+            storage.read_bucket(bucket).await?;
+            // the fake does not authenticate anyone, so every permission is granted
+            let permissions = parts
+                .uri
+                .query()
+                .unwrap_or("")
+                .split('&')
+                .filter_map(|pair| pair.strip_prefix("permissions="))
+                .map(|p| percent_decode_str(p).decode_utf8_lossy().into_owned())
+                .collect::<Vec<_>>();
+            ok(json!({
+                "kind": "storage#testIamPermissionsResponse",
+                "permissions": permissions,
+            }))
+        }
+        (false, method, ["b", bucket, list @ ("acl" | "defaultObjectAcl"), entity @ ..])
+            if entity.len() <= 1 =>
+        {
+            storage.read_bucket(bucket).await?;
+            let kind = if *list == "acl" {
+                "storage#bucketAccessControl"
+            } else {
+                "storage#objectAccessControl"
+            };
+            shared.access_control(method, kind, bucket, None, list, entity.first(), &body)
+        }
+        (false, method, ["b", bucket, "o", object, "acl", entity @ ..]) if entity.len() <= 1 => {
+            storage
+                .read_object(bucket, object, &Default::default())
+                .await?;
+            let kind = "storage#objectAccessControl";
+            shared.access_control(
+                method,
+                kind,
+                bucket,
+                Some(object),
+                "acl",
+                entity.first(),
+                &body,
+            )
+        }
+        (false, "GET", ["b", bucket, "o"]) => {
+            let list_request = ListRequest {
+                delimiter: query.get("delimiter").cloned(),
+                end_offset: query.get("endOffset").cloned(),
+                include_trailing_delimiter: query
+                    .get("includeTrailingDelimiter")
+                    .map(|v| v == "true"),
+                max_results: query_int(&query, "maxResults")?.map(|n| n.max(0) as usize),
+                page_token: query.get("pageToken").cloned(),
+                prefix: query.get("prefix").cloned(),
+                start_offset: query.get("startOffset").cloned(),
+                ..Default::default()
+            };
+            let list = storage.list_objects(bucket, &list_request).await?;
+            ok(json!({
+                "kind": "storage#objects",
+                "items": list.items.iter().map(object_json).collect::<Vec<_>>(),
+                "prefixes": list.prefixes,
+                "nextPageToken": list.next_page_token,
+            }))
+        }
+        (false, "GET", ["b", bucket, "o", object]) => {
+            let preconditions = preconditions(&query)?;
+            if query.get("alt").map(String::as_str) == Some("media") {
+                let metadata = storage.read_object(bucket, object, &preconditions).await?;
+                let data = storage
+                    .download_object(bucket, object, &preconditions)
+                    .await?;
+                let content_type = metadata
+                    .content_type
+                    .unwrap_or_else(|| "application/octet-stream".to_string());
+                Ok(Response::builder()
+                    .header(CONTENT_TYPE, content_type)
+                    .header("x-goog-generation", metadata.generation)
+                    .body(Body::from(data))
+                    .expect("valid response"))
+            } else {
+                ok(object_json(
+                    &storage.read_object(bucket, object, &preconditions).await?,
+                ))
+            }
+        }
+        (false, "PUT", ["b", bucket, "o", object])
+        | (false, "PATCH", ["b", bucket, "o", object]) => {
+            let preconditions = preconditions(&query)?;
+            let metadata: ObjectMetadata = parse_body(&body)?;
+            let mut current = storage
+                .read_object(bucket, object, &Default::default())
+                .await?;
+            metadata.apply(&mut current);
+            ok(object_json(
+                &storage.update_object(&current, &preconditions).await?,
+            ))
+        }
+        (false, "DELETE", ["b", bucket, "o", object]) => {
+            storage
+                .delete_object(bucket, object, &preconditions(&query)?)
+                .await?;
+            lock(&shared.acls).remove(&format!("b/{}/o/{}/acl", bucket, object));
+            Ok(no_content())
+        }
+        (false, "POST", ["projects", project, "hmacKeys"]) => {
+            let email = query
+                .get("serviceAccountEmail")
+                .ok_or_else(|| bad_request("Required parameter: serviceAccountEmail"))?;
+            let access_id = format!("GOOG1{}", crate::retry::idempotency_token().to_uppercase());
+            let now = chrono::Utc::now().to_rfc3339();
+            let metadata = json!({
+                "kind": "storage#hmacKeyMetadata",
+                "id": format!("{}/{}", project, access_id),
+                "selfLink": format!("{}/storage/v1/projects/{}/hmacKeys/{}", shared.endpoint, project, access_id),
+                "accessId": access_id,
+                "projectId": project,
+                "serviceAccountEmail": email,
+                "state": "ACTIVE",
+                "timeCreated": now,
+                "updated": now,
+                "etag": "CAE=",
+            });
+            lock(&shared.hmac_keys).insert(access_id, metadata.clone());
+            ok(json!({
+                "kind": "storage#hmacKey",
+                "metadata": metadata,
+                "secret": base64::encode(crate::retry::idempotency_token()),
+            }))
+        }
+        (false, "GET", ["projects", project, "hmacKeys"]) => {
+            let keys = lock(&shared.hmac_keys);
+            let items = keys
+                .values()
+                .filter(|key| key["projectId"] == *project)
+                .cloned()
+                .collect::<Vec<_>>();
+            ok(json!({ "kind": "storage#hmacKeysMetadata", "items": items }))
+        }
+        (false, "GET", ["projects", _, "hmacKeys", access_id]) => {
+            let keys = lock(&shared.hmac_keys);
+            let key = keys.get(*access_id).ok_or_else(|| not_found("Not Found"))?;
+            ok(key.clone())
+        }
+        (false, "PUT", ["projects", _, "hmacKeys", access_id]) => {
+            let update: Value = parse_body(&body)?;
+            let state = match update.get("state").and_then(Value::as_str) {
+                Some(state @ ("ACTIVE" | "INACTIVE")) => state,
+                _ => return Err(bad_request("Invalid value for state")),
+            };
+            let mut keys = lock(&shared.hmac_keys);
+            let key = keys
+                .get_mut(*access_id)
+                .ok_or_else(|| not_found("Not Found"))?;
+            key["state"] = json!(state);
+            key["updated"] = json!(chrono::Utc::now().to_rfc3339());
+            ok(key.clone())
+        }
+        (false, "DELETE", ["projects", _, "hmacKeys", access_id]) => {
+            let mut keys = lock(&shared.hmac_keys);
+            let key = keys.get(*access_id).ok_or_else(|| not_found("Not Found"))?;
+            if key["state"] != "INACTIVE" {
+                return Err(bad_request("Cannot delete keys in 'ACTIVE' state."));
+            }
+            keys.remove(*access_id);
+            Ok(no_content())
+        }
+        (false, "POST", ["b", bucket, "o", object, "compose"]) => {
+            let body: ComposeBody = parse_body(&body)?;
+            let req = ComposeRequest {
+                kind: "storage#composeRequest".to_string(),
+                source_objects: body
+                    .source_objects
+                    .into_iter()
+                    .map(|source| SourceObject {
+                        name: source.name,
+                        generation: source.generation,
+                        object_preconditions: source
+                            .object_preconditions
+                            .and_then(|p| p.if_generation_match)
+                            .map(|if_generation_match| ObjectPrecondition {
+                                if_generation_match,
+                            }),
+                    })
+                    .collect(),
+                destination: None,
+            };
+            let destination = body.destination.unwrap_or_default();
+            let composed =
+                storage.compose_with(bucket, &req, object, &preconditions(&query)?, |o| {
+                    destination.apply(o)
+                })?;
+            ok(object_json(&composed))
+        }
+        (false, "POST", ["b", bucket, "o", object, action, "b", to_bucket, "o", to_object])
+            if *action == "copyTo" || *action == "rewriteTo" =>
+        {
+            let source = storage
+                .read_object(bucket, object, &Default::default())
+                .await?;
+            let copy = storage
+                .copy_object(&source, to_bucket, to_object, &preconditions(&query)?)
+                .await?;
+            if *action == "copyTo" {
+                ok(object_json(&copy))
+            } else {
+                ok(json!({
+                    "kind": "storage#rewriteResponse",
+                    "totalBytesRewritten": copy.size.to_string(),
+                    "objectSize": copy.size.to_string(),
+                    "done": true,
+                    "resource": object_json(&copy),
+                }))
+            }
+        }
+        (true, "POST", ["b", bucket, "o"]) => {
+            let preconditions = preconditions(&query)?;
+            let content_type = header("content-type").unwrap_or("application/octet-stream");
+            match query.get("uploadType").map(String::as_str) {
+                Some("media") => {
+                    let name = query
+                        .get("name")
+                        .ok_or_else(|| bad_request("Required parameter: name"))?;
+                    let object =
+                        storage.create_with(bucket, body.to_vec(), name, &preconditions, |o| {
+                            o.content_type = Some(content_type.to_string())
+                        })?;
+                    ok(object_json(&object))
+                }
+                Some("multipart") => {
+                    let (mut metadata, media_type, data) = parse_multipart(content_type, &body)?;
+                    let name = metadata
+                        .name
+                        .clone()
+                        .or_else(|| query.get("name").cloned())
+                        .ok_or_else(|| bad_request("Required parameter: name"))?;
+                    if metadata.content_type.is_none() {
+                        metadata.content_type = media_type;
+                    }
+                    let object = storage
+                        .create_with(bucket, data, &name, &preconditions, |o| metadata.apply(o))?;
+                    ok(object_json(&object))
+                }
+                Some("resumable") => {
+                    let mut metadata: ObjectMetadata = parse_body(&body)?;
+                    let name = metadata
+                        .name
+                        .clone()
+                        .or_else(|| query.get("name").cloned())
+                        .ok_or_else(|| bad_request("Required parameter: name"))?;
+                    if metadata.content_type.is_none() {
+                        metadata.content_type = header("x-upload-content-type").map(String::from);
+                    }
+                    storage.read_bucket(bucket).await?;
+                    let upload_id = crate::retry::idempotency_token();
+                    let location = format!(
+                        "{}/upload/storage/v1/b/{}/o?uploadType=resumable&upload_id={}",
+                        shared.endpoint,
+                        crate::object::percent_encode(bucket),
+                        upload_id
+                    );
+                    shared.uploads().insert(
+                        upload_id,
+                        Upload {
+                            bucket: bucket.to_string(),
+                            name,
+                            metadata,
+                            preconditions,
+                            data: Vec::new(),
+                        },
+                    );
+                    Ok(Response::builder()
+                        .header(LOCATION, location)
+                        .body(Body::empty())
+                        .expect("valid response"))
+                }
+                _ => Err(bad_request("Invalid value for parameter uploadType")),
+            }
+        }
+        (true, "PUT", ["b", _, "o"]) => {
+            let upload_id = query
+                .get("upload_id")
+                .ok_or_else(|| bad_request("Required parameter: upload_id"))?;
+            shared.upload_chunk(upload_id, header("content-range"), body)
+        }
+        (true, "DELETE", ["b", _, "o"]) => {
+            let upload_id = query
+                .get("upload_id")
+                .ok_or_else(|| bad_request("Required parameter: upload_id"))?;
+            shared
+                .uploads()
+                .remove(upload_id)
+                .ok_or_else(|| not_found("No such upload"))?;
+            Ok(Response::builder()
+                .status(StatusCode::from_u16(499).expect("valid status code"))
+                .body(Body::empty())
+                .expect("valid response"))
+        }
+        _ => Err(not_found("Not Found")),
+    }
+}
+
+impl Shared {
+    fn uploads(&self) -> std::sync::MutexGuard<'_, HashMap<String, Upload>> {
+        lock(&self.uploads)
+    }
+
+    /// Lists, creates, reads, updates or deletes the entries of the access control list `list` of
+    /// a bucket, or of an object if `object` is set. An update of an entity that is not in the
+    /// list adds it.
+    #[allow(clippy::too_many_arguments)]
+    fn access_control(
+        &self,
+        method: &str,
+        kind: &str,
+        bucket: &str,
+        object: Option<&str>,
+        list: &str,
+        entity: Option<&&str>,
+        body: &[u8],
+    ) -> crate::Result<Response<Body>> {
+        let path = match object {
+            Some(object) => format!("b/{}/o/{}/{}", bucket, object, list),
+            None => format!("b/{}/{}", bucket, list),
+        };
+        let mut acls = lock(&self.acls);
+        let entries = acls.entry(path.clone()).or_default();
+        let position = |entity: &str| entries.iter().position(|e| e["entity"] == entity);
+        match (method, entity) {
+            ("GET", None) => ok(json!({
+                "kind": format!("{}s", kind),
+                "items": entries,
+            })),
+            ("POST", None) | ("PUT", Some(_)) | ("PATCH", Some(_)) => {
+                let new: AccessControlBody = parse_body(body)?;
+                let entity = entity.map_or(new.entity.as_str(), |e| *e);
+                let entry = access_control_json(
+                    kind,
+                    &self.endpoint,
+                    &path,
+                    bucket,
+                    object,
+                    entity,
+                    &new.role,
+                );
+                match position(entity) {
+                    Some(i) => entries[i] = entry.clone(),
+                    None => entries.push(entry.clone()),
+                }
+                ok(entry)
+            }
+            ("GET", Some(entity)) => {
+                let i = position(entity).ok_or_else(|| not_found("Not Found"))?;
+                ok(entries[i].clone())
+            }
+            ("DELETE", Some(entity)) => {
+                let i = position(entity).ok_or_else(|| not_found("Not Found"))?;
+                entries.remove(i);
+                Ok(no_content())
+            }
+            _ => Err(not_found("Not Found")),
+        }
+    }
+
+    /// Adds `body` to the resumable upload `upload_id` at the offset given by `content_range`, and
+    /// creates the object once all of its bytes have been received.
+    fn upload_chunk(
+        &self,
+        upload_id: &str,
+        content_range: Option<&str>,
+        body: Bytes,
+    ) -> crate::Result<Response<Body>> {
+        let mut uploads = self.uploads();
+        let upload = uploads
+            .get_mut(upload_id)
+            .ok_or_else(|| not_found("No such upload"))?;
+        let (start, total) = parse_content_range(content_range, body.len())?;
+        if let Some(start) = start {
+            if start > upload.data.len() {
+                return Err(bad_request("Missing bytes before the start of this chunk"));
+            }
+            upload.data.truncate(start);
+            upload.data.extend_from_slice(&body);
+        }
+        let received = upload.data.len();
+        match total {
+            Some(total) if received == total => {
+                let upload = uploads.remove(upload_id).expect("the upload exists");
+                drop(uploads);
+                let metadata = upload.metadata;
+                let object = self.storage.create_with(
+                    &upload.bucket,
+                    upload.data,
+                    &upload.name,
+                    &upload.preconditions,
+                    |o| metadata.apply(o),
+                )?;
+                ok(object_json(&object))
+            }
+            Some(total) if received > total => {
+                Err(bad_request("The upload is larger than its declared size"))
+            }
+            _ => {
+                let mut response = Response::builder().status(StatusCode::PERMANENT_REDIRECT);
+                if received > 0 {
+                    response = response.header(RANGE, format!("bytes=0-{}", received - 1));
+                }
+                Ok(response.body(Body::empty()).expect("valid response"))
+            }
+        }
+    }
+}
+
+/// Parses a `Content-Range` header of the form `bytes first-last/total`, `bytes */total` or
+/// `bytes first-last/*` into the offset of the chunk and the total size of the upload. A missing
+/// header means that the chunk is the whole upload.
+fn parse_content_range(
+    content_range: Option<&str>,
+    length: usize,
+) -> crate::Result<(Option<usize>, Option<usize>)> {
+    let content_range = match content_range {
+        Some(content_range) => content_range,
+        None => return Ok((Some(0), Some(length))),
+    };
+    let invalid = || bad_request("Invalid Content-Range header");
+    let (range, total) = content_range
+        .strip_prefix("bytes ")
+        .and_then(|r| r.split_once('/'))
+        .ok_or_else(invalid)?;
+    let total = match total {
+        "*" => None,
+        total => Some(total.parse().map_err(|_| invalid())?),
+    };
+    let start = match range {
+        "*" => None,
+        range => {
+            let (first, last) = range.split_once('-').ok_or_else(invalid)?;
+            let first: usize = first.parse().map_err(|_| invalid())?;
+            let last: usize = last.parse().map_err(|_| invalid())?;
+            if last + 1 < first || last + 1 - first != length {
+                return Err(invalid());
+            }
+            Some(first)
+        }
+    };
+    Ok((start, total))
+}
+
+/// Splits a `multipart/related` upload into its metadata, the content type of its media and the
+/// media itself.
+fn parse_multipart(
+    content_type: &str,
+    body: &[u8],
+) -> crate::Result<(ObjectMetadata, Option<String>, Vec<u8>)> {
+    let invalid = || bad_request("Invalid multipart request");
+    let boundary = content_type
+        .split(';')
+        .find_map(|p| p.trim().strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"'))
+        .ok_or_else(invalid)?;
+    let delimiter = format!("--{}", boundary);
+    let mut parts = split(body, delimiter.as_bytes()).into_iter().skip(1);
+    let mut next_part = || -> crate::Result<(&[u8], &[u8])> {
+        let part = parts.next().ok_or_else(invalid)?;
+        let part = part.strip_prefix(b"\r\n").ok_or_else(invalid)?;
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        let headers_end = find(part, b"\r\n\r\n").ok_or_else(invalid)?;
+        Ok((&part[..headers_end], &part[headers_end + 4..]))
+    };
+    let (_, metadata) = next_part()?;
+    let (headers, media) = next_part()?;
+    let media_type = String::from_utf8_lossy(headers).lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.trim().eq_ignore_ascii_case("content-type") {
+            Some(value.trim().to_string())
+        } else {
+            None
+        }
+    });
+    Ok((parse_body(metadata)?, media_type, media.to_vec()))
+}
+
+fn split<'a>(data: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    let mut rest = data;
+    while let Some(i) = find(rest, delimiter) {
+        parts.push(&rest[..i]);
+        rest = &rest[i + delimiter.len()..];
+    }
+    parts.push(rest);
+    parts
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    let decode = |s: &str| {
+        percent_decode_str(&s.replace('+', " "))
+            .decode_utf8_lossy()
+            .into_owned()
+    };
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn query_int(query: &HashMap<String, String>, key: &str) -> crate::Result<Option<i64>> {
+    query
+        .get(key)
+        .map(|v| v.parse())
+        .transpose()
+        .map_err(|_| bad_request(&format!("Invalid value for parameter {}", key)))
+}
+
+fn preconditions(query: &HashMap<String, String>) -> crate::Result<Preconditions> {
+    Ok(Preconditions {
+        if_generation_match: query_int(query, "ifGenerationMatch")?,
+        if_generation_not_match: query_int(query, "ifGenerationNotMatch")?,
+        if_metageneration_match: query_int(query, "ifMetagenerationMatch")?,
+        if_metageneration_not_match: query_int(query, "ifMetagenerationNotMatch")?,
+    })
+}
+
+/// Parses a JSON request body, where an empty body counts as an empty object.
+fn parse_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> crate::Result<T> {
+    let body = if body.iter().all(u8::is_ascii_whitespace) {
+        &b"{}"[..]
+    } else {
+        body
+    };
+    serde_json::from_slice(body).map_err(|e| bad_request(&e.to_string()))
+}
+
+/// The body of a request that creates or updates an access control entry.
+#[derive(Debug, serde::Deserialize)]
+struct AccessControlBody {
+    entity: String,
+    role: String,
+}
+
+fn access_control_json(
+    kind: &str,
+    endpoint: &str,
+    path: &str,
+    bucket: &str,
+    object: Option<&str>,
+    entity: &str,
+    role: &str,
+) -> Value {
+    let mut entry = json!({
+        "kind": kind,
+        "id": format!("{}/{}", path, entity),
+        "selfLink": format!("{}/storage/v1/{}/{}", endpoint, path, entity),
+        "bucket": bucket,
+        "entity": entity,
+        "role": role,
+        "etag": "CAE=",
+    });
+    if let Some(object) = object {
+        entry["object"] = json!(object);
+    }
+    entry
+}
+
+fn policy_json(bucket: &str, bindings: Value) -> Value {
+    json!({
+        "kind": "storage#policy",
+        "resourceId": format!("projects/_/buckets/{}", bucket),
+        "version": 1,
+        "bindings": bindings,
+        "etag": "CAE=",
+    })
+}
+
+/// Serves the XML API requests that signed urls are used for, which download an object with
+/// `GET` or `HEAD /{bucket}/{object}` and upload one with `PUT`. Like emulators, the fake does not
+/// check their signatures.
+async fn xml_request(
+    shared: &Shared,
+    parts: &hyper::http::request::Parts,
+    query: &HashMap<String, String>,
+    body: Bytes,
+) -> crate::Result<Response<Body>> {
+    let path = parts.uri.path();
+    let (bucket, object) = path[1..]
+        .split_once('/')
+        .ok_or_else(|| not_found("Not Found"))?;
+    let bucket = percent_decode_str(bucket).decode_utf8_lossy();
+    let object = percent_decode_str(object).decode_utf8_lossy();
+    let storage = &shared.storage;
+    match parts.method.as_str() {
+        "GET" | "HEAD" => {
+            let preconditions = Preconditions::default();
+            let metadata = storage
+                .read_object(&bucket, &object, &preconditions)
+                .await?;
+            let data = storage
+                .download_object(&bucket, &object, &preconditions)
+                .await?;
+            let content_type = metadata
+                .content_type
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            let mut response = Response::builder()
+                .header(CONTENT_TYPE, content_type)
+                .header("x-goog-generation", metadata.generation);
+            if let Some(disposition) = query
+                .get("response-content-disposition")
+                .or(metadata.content_disposition.as_ref())
+            {
+                response = response.header(CONTENT_DISPOSITION, disposition.as_str());
+            }
+            Ok(response.body(Body::from(data)).expect("valid response"))
+        }
+        "PUT" => {
+            let content_type = parts
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("application/octet-stream")
+                .to_string();
+            let metadata = parts
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    let field = name.as_str().strip_prefix("x-goog-meta-")?;
+                    Some((field.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect::<HashMap<_, _>>();
+            let object =
+                storage.create_with(&bucket, body.to_vec(), &object, &Default::default(), |o| {
+                    o.content_type = Some(content_type);
+                    if !metadata.is_empty() {
+                        o.metadata = Some(metadata);
+                    }
+                })?;
+            Ok(Response::builder()
+                .header("x-goog-generation", object.generation)
+                .body(Body::empty())
+                .expect("valid response"))
+        }
+        _ => Err(not_found("Not Found")),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // a panic while holding the lock cannot leave the maps in an inconsistent state
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Serializes `object` the way Google does.
+fn object_json(object: &Object) -> Value {
+    let mut value = serde_json::to_value(object).expect("objects serialize to JSON");
+    stringify(&mut value, &["generation", "metageneration", "size"]);
+    value
+}
+
+/// Serializes `bucket` the way Google does.
+fn bucket_json(bucket: &Bucket) -> Value {
+    let mut value = serde_json::to_value(bucket).expect("buckets serialize to JSON");
+    stringify(&mut value, &["projectNumber", "metageneration"]);
+    if let Some(policy) = value.get_mut("retentionPolicy") {
+        stringify(policy, &["retentionPeriod"]);
+    }
+    value
+}
+
+/// Removes the fields that are not set, which Google omits, and turns the integer fields `keys`
+/// into strings.
+fn stringify(value: &mut Value, keys: &[&str]) {
+    if let Value::Object(fields) = value {
+        fields.retain(|_, field| !field.is_null());
+    }
+    for key in keys {
+        if let Some(field) = value.get_mut(*key).filter(|f| f.is_number()) {
+            *field = Value::String(field.to_string());
+        }
+    }
+}
+
+fn ok(value: Value) -> crate::Result<Response<Body>> {
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json; charset=UTF-8")
+        .body(Body::from(value.to_string()))
+        .expect("valid response"))
+}
+
+fn no_content() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .expect("valid response")
+}
+
+fn not_found(message: &str) -> crate::Error {
+    storage::error(StatusCode::NOT_FOUND, Reason::NotFound, message)
+}
+
+fn bad_request(message: &str) -> crate::Error {
+    storage::error(StatusCode::BAD_REQUEST, Reason::Invalid, message)
+}
+
+fn error_response(error: crate::Error) -> Response<Body> {
+    match error {
+        crate::Error::Http(error) => Response::builder()
+            .status(error.status)
+            .header(CONTENT_TYPE, "application/json; charset=UTF-8")
+            .body(Body::from(error.body))
+            .expect("valid response"),
+        other => error_response(storage::error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Reason::InternalError,
+            &other.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bucket::Versioning;
+    use futures_util::TryStreamExt;
+    use hyper::header::CONTENT_RANGE;
+
+    async fn server_with_bucket() -> Result<(FakeServer, crate::Client), Box<dyn std::error::Error>>
+    {
+        let server = FakeServer::start()?;
+        let client = server.client()?;
+        client
+            .bucket()
+            .create(&NewBucket {
+                name: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await?;
+        Ok((server, client))
+    }
+
+    #[tokio::test]
+    async fn buckets() -> Result<(), Box<dyn std::error::Error>> {
+        let server = FakeServer::start()?;
+        let client = server.client()?;
+        let created = client
+            .bucket()
+            .create(&NewBucket {
+                name: "bucket".to_string(),
+                versioning: Some(Versioning { enabled: true }),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(created.versioning, Some(Versioning { enabled: true }));
+        assert_eq!(client.bucket().read("bucket").await?, created);
+        assert_eq!(client.bucket().list().await?, vec![created.clone()]);
+
+        let mut bucket = created;
+        bucket.labels = Some(
+            vec![("team".to_string(), "storage".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let updated = client.bucket().update(&bucket).await?;
+        assert_eq!(updated.metageneration, 2);
+        assert_eq!(updated.labels, bucket.labels);
+
+        client.bucket().delete(updated).await?;
+        let err = client.bucket().read("bucket").await.unwrap_err();
+        assert!(err.is_not_found());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn objects() -> Result<(), Box<dyn std::error::Error>> {
+        let (server, client) = server_with_bucket().await?;
+        let created = client
+            .object()
+            .create("bucket", b"hello".to_vec(), "dir/file 1.txt", "text/plain")
+            .await?;
+        assert_eq!(created.size, 5);
+        assert_eq!(
+            client.object().read("bucket", "dir/file 1.txt").await?,
+            created
+        );
+        assert_eq!(
+            client.object().download("bucket", "dir/file 1.txt").await?,
+            b"hello"
+        );
+        let streamed: Vec<u8> = client
+            .object()
+            .download_streamed("bucket", "dir/file 1.txt")
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(streamed, b"hello");
+
+        let mut object = created.clone();
+        object.content_type = Some("text/markdown".to_string());
+        let updated = client.object().update(&object).await?;
+        assert_eq!(updated.metageneration, 2);
+        assert_eq!(updated.content_type.as_deref(), Some("text/markdown"));
+
+        let err = client
+            .object()
+            .with_preconditions(Preconditions {
+                if_metageneration_match: Some(1),
+                ..Default::default()
+            })
+            .delete("bucket", "dir/file 1.txt")
+            .await
+            .unwrap_err();
+        assert!(err.is_precondition_failed());
+        client
+            .object()
+            .with_preconditions(Preconditions {
+                if_metageneration_match: Some(2),
+                ..Default::default()
+            })
+            .delete("bucket", "dir/file 1.txt")
+            .await?;
+        let err = client
+            .object()
+            .read("bucket", "dir/file 1.txt")
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+        assert!(server.storage().list_buckets().await?.len() == 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list() -> Result<(), Box<dyn std::error::Error>> {
+        let (_server, client) = server_with_bucket().await?;
+        for name in &["a/1", "a/2", "b", "c"] {
+            client
+                .object()
+                .create("bucket", Vec::new(), name, "text/plain")
+                .await?;
+        }
+        let list_request = ListRequest {
+            delimiter: Some("/".to_string()),
+            max_results: Some(2),
+            ..Default::default()
+        };
+        let pages: Vec<_> = client
+            .object()
+            .list("bucket", list_request)
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].prefixes, ["a/"]);
+        let names: Vec<_> = pages
+            .iter()
+            .flat_map(|page| &page.items)
+            .map(|object| object.name.as_str())
+            .collect();
+        assert_eq!(names, ["b", "c"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn compose_copy_and_rewrite() -> Result<(), Box<dyn std::error::Error>> {
+        let (_server, client) = server_with_bucket().await?;
+        let one = client
+            .object()
+            .create("bucket", b"one".to_vec(), "one", "text/plain")
+            .await?;
+        client
+            .object()
+            .create("bucket", b"two".to_vec(), "two", "text/plain")
+            .await?;
+        let source = |name: &str, generation| SourceObject {
+            name: name.to_string(),
+            generation,
+            object_preconditions: None,
+        };
+        let compose_request = ComposeRequest {
+            kind: "storage#composeRequest".to_string(),
+            source_objects: vec![source("one", Some(one.generation)), source("two", None)],
+            destination: None,
+        };
+        let composed = client
+            .object()
+            .compose("bucket", &compose_request, "both")
+            .await?;
+        assert_eq!(composed.component_count, Some(2));
+        assert_eq!(client.object().download("bucket", "both").await?, b"onetwo");
+
+        let copy = client.object().copy(&composed, "bucket", "copy").await?;
+        assert_eq!(copy.crc32c, composed.crc32c);
+        let rewritten = client
+            .object()
+            .rewrite(&composed, "bucket", "rewritten")
+            .await?;
+        assert_eq!(rewritten.size, 6);
+        assert_eq!(
+            client.object().download("bucket", "rewritten").await?,
+            b"onetwo"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn uploads() -> Result<(), Box<dyn std::error::Error>> {
+        let (server, client) = server_with_bucket().await?;
+        let http = reqwest::Client::new();
+        let url = format!("{}/upload/storage/v1/b/bucket/o", server.endpoint());
+
+        let body = "--boundary\r\n\
+            Content-Type: application/json; charset=UTF-8\r\n\r\n\
+            {\"name\": \"multipart\", \"metadata\": {\"key\": \"value\"}}\r\n\
+            --boundary\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            multipart body\r\n\
+            --boundary--";
+        http.post(&url)
+            .query(&[("uploadType", "multipart")])
+            .header(CONTENT_TYPE, "multipart/related; boundary=boundary")
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        let object = client.object().read("bucket", "multipart").await?;
+        assert_eq!(object.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(object.metadata.unwrap()["key"], "value");
+        assert_eq!(
+            client.object().download("bucket", "multipart").await?,
+            b"multipart body"
+        );
+
+        let session = http
+            .post(&url)
+            .query(&[("uploadType", "resumable"), ("name", "resumable")])
+            .header("x-upload-content-type", "text/plain")
+            .send()
+            .await?
+            .error_for_status()?;
+        let session = session.headers()[LOCATION].to_str()?.to_string();
+        let response = http
+            .put(&session)
+            .header(CONTENT_RANGE, "bytes 0-4/*")
+            .body("hello")
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers()[RANGE], "bytes=0-4");
+        let response = http
+            .put(&session)
+            .header(CONTENT_RANGE, "bytes 5-10/11")
+            .body(" world")
+            .send()
+            .await?
+            .error_for_status()?;
+        let object: serde_json::Value = response.json().await?;
+        assert_eq!(object["size"], "11");
+        assert_eq!(
+            client.object().download("bucket", "resumable").await?,
+            b"hello world"
+        );
+        let pages: Vec<_> = client
+            .object()
+            .list("bucket", ListRequest::default())
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(pages[0].items.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn access_controls_and_policies() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::bucket::{Binding, IamPolicy, IamRole, StandardIamRole};
+        use crate::bucket_access_control::{Entity, NewBucketAccessControl, Role};
+        use crate::object_access_control::NewObjectAccessControl;
+
+        let (_server, client) = server_with_bucket().await?;
+        let new_acl = NewBucketAccessControl {
+            entity: Entity::AllUsers,
+            role: Role::Reader,
+        };
+        let mut acl = client
+            .bucket_access_control()
+            .create("bucket", &new_acl)
+            .await?;
+        assert_eq!(acl.bucket, "bucket");
+        acl.role = Role::Writer;
+        client.bucket_access_control().update(&acl).await?;
+        let read = client
+            .bucket_access_control()
+            .read("bucket", &Entity::AllUsers)
+            .await?;
+        assert_eq!(read.role, Role::Writer);
+        assert_eq!(
+            client.bucket_access_control().list("bucket").await?.len(),
+            1
+        );
+        client.bucket_access_control().delete(read).await?;
+        assert!(client
+            .bucket_access_control()
+            .list("bucket")
+            .await?
+            .is_empty());
+
+        client
+            .object()
+            .create("bucket", vec![0], "file", "text/plain")
+            .await?;
+        let new_acl = NewObjectAccessControl {
+            entity: Entity::AllUsers,
+            role: Role::Reader,
+        };
+        client
+            .object_access_control()
+            .create("bucket", "file", &new_acl)
+            .await?;
+        let acl = client
+            .object_access_control()
+            .read("bucket", "file", &Entity::AllUsers)
+            .await?;
+        assert_eq!(acl.object, "file");
+        let err = client
+            .object_access_control()
+            .read("bucket", "missing", &Entity::AllUsers)
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+
+        let bucket = client.bucket().read("bucket").await?;
+        let policy = IamPolicy {
+            bindings: vec![Binding {
+                role: IamRole::Standard(StandardIamRole::ObjectViewer),
+                members: vec!["allUsers".to_string()],
+                condition: None,
+            }],
+            ..Default::default()
+        };
+        assert!(client
+            .bucket()
+            .get_iam_policy(&bucket)
+            .await?
+            .bindings
+            .is_empty());
+        client.bucket().set_iam_policy(&bucket, &policy).await?;
+        let read = client.bucket().get_iam_policy(&bucket).await?;
+        assert_eq!(read.bindings, policy.bindings);
+        assert_eq!(
+            read.resource_id.as_deref(),
+            Some("projects/_/buckets/bucket")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hmac_keys() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::hmac_key::HmacState;
+
+        let server = FakeServer::start()?;
+        let client = server
+            .client_builder()
+            .service_account_email("test@test.iam.gserviceaccount.com")
+            .build()?;
+        let key = client.hmac_key().create().await?;
+        let access_id = &key.metadata.access_id;
+        assert_eq!(key.metadata.state, HmacState::Active);
+        assert_eq!(
+            key.metadata.service_account_email,
+            "test@test.iam.gserviceaccount.com"
+        );
+        assert_eq!(client.hmac_key().read(access_id).await?, key.metadata);
+        assert!(client.hmac_key().delete(access_id).await.is_err());
+        let updated = client
+            .hmac_key()
+            .update(access_id, HmacState::Inactive)
+            .await?;
+        assert_eq!(updated.state, HmacState::Inactive);
+        assert_eq!(client.hmac_key().list().await?, vec![updated]);
+        client.hmac_key().delete(access_id).await?;
+        assert!(client.hmac_key().list().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn signed_urls() -> Result<(), Box<dyn std::error::Error>> {
+        let server = FakeServer::start()?;
+        let client = server
+            .client_builder()
+            .signer(crate::HmacSigner::new("GOOG1EXAMPLE", "secret"))
+            .build()?;
+        client
+            .bucket()
+            .create(&NewBucket {
+                name: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await?;
+        let object = client
+            .object()
+            .create("bucket", vec![0], "dir/ä file", "text/plain")
+            .await?;
+        let http = reqwest::Client::new();
+
+        let metadata = vec![("field".to_string(), "value".to_string())]
+            .into_iter()
+            .collect();
+        let (url, headers) = client
+            .object()
+            .upload_url_with(&object, 100, metadata)
+            .await?;
+        assert!(url.starts_with(&format!("{}/bucket/dir/%C3%A4%20file?", server.endpoint())));
+        let mut request = http.put(&url).body("hello");
+        for (name, value) in &headers {
+            request = request.header(name, value);
+        }
+        request.send().await?.error_for_status()?;
+        let object = client.object().read("bucket", "dir/ä file").await?;
+        assert_eq!(object.metadata.as_ref().unwrap()["field"], "value");
+
+        let options = crate::DownloadOptions::new().content_disposition("attachment");
+        let url = client
+            .object()
+            .download_url_with(&object, 100, options)
+            .await?;
+        let response = http.get(&url).send().await?.error_for_status()?;
+        assert_eq!(response.headers()[CONTENT_DISPOSITION], "attachment");
+        assert_eq!(response.text().await?, "hello");
+        Ok(())
+    }
+}
@@ -1,5 +1,14 @@
 use std::fmt::{Display, Formatter};
 
+use crate::resources::service_account::ServiceAccount;
+
+// A token is considered expired this many seconds before it actually expires.
+const EXPIRY_MARGIN: u64 = 300;
+// The background refresh task renews the token this many seconds before `EXPIRY_MARGIN` is hit.
+const REFRESH_AHEAD: u64 = 60;
+// The time the background refresh task waits after refreshing, or after failing to refresh.
+const MIN_REFRESH_INTERVAL: u64 = 10;
+
 /// Trait that refreshes a token when it is expired
 #[async_trait::async_trait]
 pub trait TokenCache: Sync {
@@ -13,14 +22,36 @@ pub trait TokenCache: Sync {
     /// Returns the intended scope for the current token.
     async fn scope(&self) -> String;
 
+    /// Returns a lock that is held while the token is refreshed. When this returns `Some`,
+    /// concurrent callers of `get` that find the token expired wait for a single refresh instead
+    /// of each fetching a token of their own.
+    ///
+    /// The default implementation returns `None`, which leaves calls to `get` and `refresh`
+    /// unsynchronized. A [`Client`](crate::Client) then refreshes the cache under a lock of its
+    /// own, so its requests still share a single refresh, but callers that use the cache outside
+    /// of a client should override this.
+    fn refresh_lock(&self) -> Option<&tokio::sync::Mutex<()>> {
+        None
+    }
+
     /// Returns a valid, unexpired token. If the contained token is expired, it updates and returns
     /// the token.
     async fn get(&self, client: &reqwest::Client) -> crate::Result<String> {
+        if let Some((token, exp)) = self.token_and_exp().await {
+            if now() + EXPIRY_MARGIN < exp {
+                return Ok(token);
+            }
+        }
+
+        let _guard = match self.refresh_lock() {
+            Some(lock) => Some(lock.lock().await),
+            None => None,
+        };
+        // another caller may have refreshed the token while we were waiting for the lock
         match self.token_and_exp().await {
-            Some((token, exp)) if now() + 300 < exp => Ok(token),
+            Some((token, exp)) if now() + EXPIRY_MARGIN < exp => Ok(token),
             _ => {
-                let (token, exp) = self.fetch_token(client).await?;
-                self.set_token(token, exp).await?;
+                fetch_and_set(self, client).await?;
 
                 self.token_and_exp()
                     .await
@@ -30,6 +61,15 @@ pub trait TokenCache: Sync {
         }
     }
 
+    /// Fetches a new token and stores it, regardless of whether the current token is expired.
+    async fn refresh(&self, client: &reqwest::Client) -> crate::Result<()> {
+        let _guard = match self.refresh_lock() {
+            Some(lock) => Some(lock.lock().await),
+            None => None,
+        };
+        fetch_and_set(self, client).await
+    }
+
     /// Fetches and returns the token using the service account
     async fn fetch_token(&self, client: &reqwest::Client) -> crate::Result<(String, u64)>;
 }
@@ -43,6 +83,15 @@ struct Claims {
     iat: u64,
 }
 
+#[derive(serde::Serialize)]
+struct SelfSignedClaims {
+    iss: String,
+    sub: String,
+    scope: String,
+    exp: u64,
+    iat: u64,
+}
+
 #[derive(serde::Deserialize, Debug)]
 // #[allow(dead_code)]
 struct TokenResponse {
@@ -58,6 +107,8 @@ pub struct Token {
     token: tokio::sync::RwLock<Option<DefaultTokenData>>,
     // store the access scope for later use if we need to refresh the token
     access_scope: String,
+    // held while fetching a new token, so concurrent callers share a single refresh
+    refresh_lock: tokio::sync::Mutex<()>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,10 +127,13 @@ impl Default for Token {
 }
 
 impl Token {
-    pub(crate) fn new(scope: &str) -> Self {
+    /// Creates a `Token` for the provided scope, which is fetched using the service account found
+    /// in the `SERVICE_ACCOUNT` environment parameter.
+    pub fn new(scope: &str) -> Self {
         Self {
             token: tokio::sync::RwLock::new(None),
             access_scope: scope.to_string(),
+            refresh_lock: tokio::sync::Mutex::new(()),
         }
     }
 }
@@ -90,6 +144,10 @@ impl TokenCache for Token {
         self.access_scope.clone()
     }
 
+    fn refresh_lock(&self) -> Option<&tokio::sync::Mutex<()>> {
+        Some(&self.refresh_lock)
+    }
+
     async fn token_and_exp(&self) -> Option<(String, u64)> {
         self.token.read().await.as_ref().map(|d| (d.0.clone(), d.1))
     }
@@ -105,7 +163,7 @@ impl TokenCache for Token {
 
         let claims = Claims {
             iss: crate::SERVICE_ACCOUNT.client_email.clone(),
-            scope: self.scope().await.into(),
+            scope: self.scope().await,
             aud: "https://www.googleapis.com/oauth2/v4/token".to_string(),
             exp,
             iat: now,
@@ -132,9 +190,424 @@ impl TokenCache for Token {
     }
 }
 
+/// A `TokenCache` that mints self-signed JWTs locally from the service account key, instead of
+/// exchanging a JWT for an access token at the OAuth endpoint. Google accepts these JWTs directly
+/// as bearer tokens for service accounts, so obtaining or refreshing a token does not require a
+/// network round trip.
+///
+/// ### Example
+/// ```no_run
+/// use cloud_storage::{Client, SelfSignedToken};
+///
+/// let client = Client::with_cache(SelfSignedToken::default());
+/// ```
+pub struct SelfSignedToken {
+    token: tokio::sync::RwLock<Option<DefaultTokenData>>,
+    access_scope: String,
+    refresh_lock: tokio::sync::Mutex<()>,
+    // when `None`, the service account from the `SERVICE_ACCOUNT` environment parameter is used
+    service_account: Option<ServiceAccount>,
+}
+
+impl Default for SelfSignedToken {
+    fn default() -> Self {
+        SelfSignedToken::new("https://www.googleapis.com/auth/devstorage.full_control")
+    }
+}
+
+impl SelfSignedToken {
+    /// Creates a `SelfSignedToken` for the provided scope, signed with the key of the service
+    /// account found in the `SERVICE_ACCOUNT` environment parameter.
+    pub fn new(scope: &str) -> Self {
+        Self {
+            token: tokio::sync::RwLock::new(None),
+            access_scope: scope.to_string(),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            service_account: None,
+        }
+    }
+
+    /// Creates a `SelfSignedToken` for the provided scope, signed with the key of the provided
+    /// service account.
+    pub fn with_service_account(service_account: ServiceAccount, scope: &str) -> Self {
+        Self {
+            service_account: Some(service_account),
+            ..Self::new(scope)
+        }
+    }
+
+    fn service_account(&self) -> &ServiceAccount {
+        self.service_account
+            .as_ref()
+            .unwrap_or_else(|| &crate::SERVICE_ACCOUNT)
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCache for SelfSignedToken {
+    async fn scope(&self) -> String {
+        self.access_scope.clone()
+    }
+
+    fn refresh_lock(&self) -> Option<&tokio::sync::Mutex<()>> {
+        Some(&self.refresh_lock)
+    }
+
+    async fn token_and_exp(&self) -> Option<(String, u64)> {
+        self.token.read().await.as_ref().map(|d| (d.0.clone(), d.1))
+    }
+
+    async fn set_token(&self, token: String, exp: u64) -> crate::Result<()> {
+        *self.token.write().await = Some(DefaultTokenData(token, exp));
+        Ok(())
+    }
+
+    async fn fetch_token(&self, _client: &reqwest::Client) -> crate::Result<(String, u64)> {
+        let now = now();
+        let exp = now + 3600;
+
+        let service_account = self.service_account();
+        let claims = SelfSignedClaims {
+            iss: service_account.client_email.clone(),
+            sub: service_account.client_email.clone(),
+            scope: self.scope().await,
+            exp,
+            iat: now,
+        };
+        let header = jsonwebtoken::Header {
+            alg: jsonwebtoken::Algorithm::RS256,
+            kid: Some(service_account.private_key_id.clone()),
+            ..Default::default()
+        };
+        let private_key_bytes = service_account.private_key.as_bytes();
+        let private_key = jsonwebtoken::EncodingKey::from_rsa_pem(private_key_bytes)?;
+        let jwt = jsonwebtoken::encode(&header, &claims, &private_key)?;
+        Ok((jwt, exp))
+    }
+}
+
+/// A `TokenCache` that fetches the token of the service account attached to the machine from the
+/// [metadata server](https://cloud.google.com/compute/docs/access/authenticate-workloads), which
+/// is available on Compute Engine, Cloud Run and on GKE with workload identity. It does not
+/// require a private key. The metadata server is reached through the `GCE_METADATA_HOST`
+/// environment parameter when it is set, and at `metadata.google.internal` otherwise.
+///
+/// ### Example
+/// ```no_run
+/// use cloud_storage::{Client, MetadataToken};
+///
+/// let client = Client::with_cache(MetadataToken::default());
+/// ```
+pub struct MetadataToken {
+    token: tokio::sync::RwLock<Option<DefaultTokenData>>,
+    access_scope: String,
+    refresh_lock: tokio::sync::Mutex<()>,
+    // such as `http://metadata.google.internal`
+    endpoint: String,
+}
+
+impl Default for MetadataToken {
+    fn default() -> Self {
+        MetadataToken::new("https://www.googleapis.com/auth/cloud-platform")
+    }
+}
+
+impl MetadataToken {
+    /// Creates a `MetadataToken` for the provided scope. The scopes of tokens from the metadata
+    /// server are usually fixed when the machine is created, so the scope is only used where the
+    /// metadata server honours it.
+    pub fn new(scope: &str) -> Self {
+        let endpoint = match std::env::var("GCE_METADATA_HOST") {
+            Ok(host) if !host.is_empty() => format!("http://{}", host),
+            _ => "http://metadata.google.internal".to_string(),
+        };
+        Self {
+            token: tokio::sync::RwLock::new(None),
+            access_scope: scope.to_string(),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            endpoint,
+        }
+    }
+
+    /// Fetches tokens from the metadata server at `endpoint`, such as `http://169.254.169.254`,
+    /// instead.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenCache for MetadataToken {
+    async fn scope(&self) -> String {
+        self.access_scope.clone()
+    }
+
+    fn refresh_lock(&self) -> Option<&tokio::sync::Mutex<()>> {
+        Some(&self.refresh_lock)
+    }
+
+    async fn token_and_exp(&self) -> Option<(String, u64)> {
+        self.token.read().await.as_ref().map(|d| (d.0.clone(), d.1))
+    }
+
+    async fn set_token(&self, token: String, exp: u64) -> crate::Result<()> {
+        *self.token.write().await = Some(DefaultTokenData(token, exp));
+        Ok(())
+    }
+
+    async fn fetch_token(&self, client: &reqwest::Client) -> crate::Result<(String, u64)> {
+        let url = format!(
+            "{}/computeMetadata/v1/instance/service-accounts/default/token",
+            self.endpoint
+        );
+        let response = client
+            .get(&url)
+            .header("Metadata-Flavor", "Google")
+            .query(&[("scopes", self.scope().await)])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(crate::error::HttpError::from_response(response)
+                .await
+                .into());
+        }
+        let response: TokenResponse = response.json().await?;
+        Ok((response.access_token, now() + response.expires_in))
+    }
+}
+
+/// Fetches a new token and stores it in `cache`, emitting an event about the outcome when the
+/// `tracing` feature is enabled.
+async fn fetch_and_set<T: TokenCache + ?Sized>(
+    cache: &T,
+    client: &reqwest::Client,
+) -> crate::Result<()> {
+    let result = match cache.fetch_token(client).await {
+        Ok((token, exp)) => cache.set_token(token, exp).await.map(|()| exp),
+        Err(e) => Err(e),
+    };
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok(exp) => tracing::debug!(expires_at = exp, "fetched a new access token"),
+        Err(error) => tracing::warn!(%error, "failed to fetch an access token"),
+    }
+    result.map(drop)
+}
+
+/// Returns the token of `cache` like `TokenCache::get`. For caches without a `refresh_lock` of
+/// their own, concurrent callers that find the token expired wait for a single refresh under
+/// `lock` instead.
+pub(crate) async fn get(
+    cache: &(dyn TokenCache + Send),
+    lock: &tokio::sync::Mutex<()>,
+    client: &reqwest::Client,
+) -> crate::Result<String> {
+    if cache.refresh_lock().is_some() {
+        return cache.get(client).await;
+    }
+    if let Some((token, exp)) = cache.token_and_exp().await {
+        if now() + EXPIRY_MARGIN < exp {
+            return Ok(token);
+        }
+    }
+    let _guard = lock.lock().await;
+    cache.get(client).await
+}
+
+/// Refreshes the token held by `cache` shortly before it would be considered expired, until the
+/// cache is dropped. Caches without a `refresh_lock` of their own are refreshed under `lock`.
+pub(crate) fn spawn_refresh(
+    cache: std::sync::Weak<dyn TokenCache + Send>,
+    lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+    client: reqwest::Client,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let cache = match cache.upgrade() {
+                Some(cache) => cache,
+                None => return,
+            };
+            let wait = match cache.token_and_exp().await {
+                Some((_, exp)) if now() + EXPIRY_MARGIN + REFRESH_AHEAD < exp => {
+                    exp - EXPIRY_MARGIN - REFRESH_AHEAD - now()
+                }
+                _ => {
+                    let _guard = match cache.refresh_lock() {
+                        Some(_) => None,
+                        None => Some(lock.lock().await),
+                    };
+                    // a failed refresh is not fatal, `TokenCache::get` fetches the token itself
+                    // if we did not manage to refresh it in time
+                    let _ = cache.refresh(&client).await;
+                    MIN_REFRESH_INTERVAL
+                }
+            };
+            drop(cache);
+            tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+        }
+    })
+}
+
 fn now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::SystemTime::UNIX_EPOCH)
         .unwrap()
         .as_secs()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct CountingCache {
+        token: tokio::sync::RwLock<Option<(String, u64)>>,
+        refresh_lock: tokio::sync::Mutex<()>,
+        without_lock: bool,
+        fetches: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenCache for CountingCache {
+        async fn token_and_exp(&self) -> Option<(String, u64)> {
+            self.token.read().await.clone()
+        }
+
+        async fn set_token(&self, token: String, exp: u64) -> crate::Result<()> {
+            *self.token.write().await = Some((token, exp));
+            Ok(())
+        }
+
+        async fn scope(&self) -> String {
+            String::new()
+        }
+
+        fn refresh_lock(&self) -> Option<&tokio::sync::Mutex<()>> {
+            match self.without_lock {
+                true => None,
+                false => Some(&self.refresh_lock),
+            }
+        }
+
+        async fn fetch_token(&self, _client: &reqwest::Client) -> crate::Result<(String, u64)> {
+            let n = self
+                .fetches
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            Ok((format!("token-{}", n), now() + 3600))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn single_flight_refresh() -> Result<(), Box<dyn std::error::Error>> {
+        let cache = std::sync::Arc::new(CountingCache::default());
+        let client = reqwest::Client::new();
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let cache = cache.clone();
+                let client = client.clone();
+                tokio::spawn(async move { cache.get(&client).await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await??, "token-0");
+        }
+        assert_eq!(cache.fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        cache.refresh(&client).await?;
+        assert_eq!(cache.get(&client).await?, "token-1");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn single_flight_refresh_without_lock() -> Result<(), Box<dyn std::error::Error>> {
+        let cache = std::sync::Arc::new(CountingCache {
+            without_lock: true,
+            ..Default::default()
+        });
+        let lock = std::sync::Arc::new(tokio::sync::Mutex::new(()));
+        let client = reqwest::Client::new();
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let (cache, lock, client) = (cache.clone(), lock.clone(), client.clone());
+                tokio::spawn(async move { get(cache.as_ref(), &lock, &client).await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await??, "token-0");
+        }
+        assert_eq!(cache.fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn background_refresh() -> Result<(), Box<dyn std::error::Error>> {
+        // still valid for `get`, but due for a refresh by the background task
+        let exp = now() + EXPIRY_MARGIN + REFRESH_AHEAD / 2;
+        let cache = std::sync::Arc::new(CountingCache {
+            token: tokio::sync::RwLock::new(Some(("old".to_string(), exp))),
+            ..Default::default()
+        });
+        let dyn_cache: std::sync::Arc<dyn TokenCache + Send> = cache.clone();
+        let task = spawn_refresh(
+            std::sync::Arc::downgrade(&dyn_cache),
+            Default::default(),
+            reqwest::Client::new(),
+        );
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+        while cache.fetches.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "token not refreshed"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let (token, exp) = loop {
+            match cache.token_and_exp().await {
+                Some((token, _)) if token == "old" => tokio::task::yield_now().await,
+                Some(token) => break token,
+                None => unreachable!(),
+            }
+        };
+        assert_eq!(token, "token-0");
+        assert!(exp > now() + EXPIRY_MARGIN + REFRESH_AHEAD);
+        assert_eq!(cache.fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+        task.abort();
+        Ok(())
+    }
+
+    #[derive(serde::Deserialize)]
+    struct DecodedClaims {
+        iss: String,
+        sub: String,
+        scope: String,
+        exp: u64,
+        iat: u64,
+    }
+
+    #[tokio::test]
+    async fn self_signed_token() -> Result<(), Box<dyn std::error::Error>> {
+        let service_account = ServiceAccount::test();
+        let email = service_account.client_email.clone();
+        let key_id = service_account.private_key_id.clone();
+        let cache = SelfSignedToken::with_service_account(service_account, "some-scope");
+
+        let token = cache.get(&reqwest::Client::new()).await?;
+        let public_key = include_bytes!("../test-data/service-account.pub.pem");
+        let decoded = jsonwebtoken::decode::<DecodedClaims>(
+            &token,
+            &jsonwebtoken::DecodingKey::from_rsa_pem(public_key)?,
+            &jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256),
+        )?;
+        assert_eq!(decoded.header.kid, Some(key_id));
+        assert_eq!(decoded.claims.iss, email);
+        assert_eq!(decoded.claims.sub, email);
+        assert_eq!(decoded.claims.scope, "some-scope");
+        assert_eq!(decoded.claims.exp, decoded.claims.iat + 3600);
+
+        // the token is cached until it is about to expire
+        assert_eq!(cache.get(&reqwest::Client::new()).await?, token);
+        Ok(())
+    }
+}
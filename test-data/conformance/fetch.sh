@@ -0,0 +1,9 @@
+#!/bin/sh
+# Downloads the V4 signing cases of Google's conformance tests, and the key that they are signed
+# with, into this directory. Both files are used as they are published.
+set -e
+cd "$(dirname "$0")"
+base=https://raw.githubusercontent.com/googleapis/conformance-tests/main/storage/v1
+for file in v4_signatures.json test_service_account.not-a-test.json; do
+    curl -fsSL -o "$file" "$base/$file"
+done
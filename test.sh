@@ -1,7 +1,14 @@
 set -e
 echo && echo '--------------------------------' && echo 'Runing sync tests'
-cargo test --features sync,global-client -- --test-threads=1
+cargo test --lib --features sync,global-client -- --test-threads=1
 echo && echo '--------------------------------' && echo 'Runing sync tests with rustls'
-cargo test --no-default-features --features sync,rustls-tls,global-client -- --test-threads=1
+cargo test --lib --no-default-features --features sync,rustls-tls,global-client -- --test-threads=1
 echo && echo '--------------------------------' && echo 'Runing sync tests with all features'
-cargo test --all-features -- --test-threads=1
+cargo test --lib --all-features -- --test-threads=1
+echo && echo '--------------------------------' && echo 'Runing conformance tests'
+[ -f test-data/conformance/v4_signatures.json ] || sh test-data/conformance/fetch.sh
+cargo test --lib --all-features conformance -- --ignored
+if [ -n "$STORAGE_EMULATOR_HOST" ] || [ -n "$SERVICE_ACCOUNT" ]; then
+    echo && echo '--------------------------------' && echo 'Runing documentation examples'
+    cargo test --doc --all-features -- --test-threads=1
+fi